@@ -21,11 +21,12 @@ fn main() {
 	let window = rsg::window("Window Title", layout);
 
 	loop {
-		let (_event, _values) = window.read();
+		let (_event, values) = window.read_values();
 
 		println!("{}", _event);
 
-		//println!("{}", _values.get(0).unwrap_or(&"".to_string()));
+		// values are looked up by key, see window.keys()
+		println!("slider: {}", values.get_f64("slider_0").unwrap_or(0.0));
 
 		if _event == "Quit".to_string() {
 			break;
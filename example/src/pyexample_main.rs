@@ -2,14 +2,14 @@ use rustsimplegui as sg;
 
 fn main() {
 	let layout = vec![	vec![sg::text("What's your name?")],
-						vec![sg::input()],
+						vec![sg::input_ex("", sg::RsgObjEx::new().focus())],
 						vec![sg::button("Ok")] ];
 
 	let window = sg::window("Window Title", layout);
 
-	let (_event, values) = window.read();
+	let (_event, values) = window.read_values();
 
-	println!("Hello {}! Thanks for trying RustSimpleGUI", values[0]);
+	println!("Hello {}! Thanks for trying RustSimpleGUI", values.expect("input_0"));
 
 	window.close();
 }
\ No newline at end of file
@@ -5,6 +5,9 @@ pub enum RsgColor {
     #[default]
     None,
     Name,
+    /// A color given as "#rrggbb" or "#rgb", the '#' being optional.
+    Hex(&'static str),
+    Rgb(u8, u8, u8),
 AliceBlue,
 Aliceblue,
 AntiqueWhite,
@@ -767,9 +770,36 @@ Yellow4,
 Yellowgreen,
 }
 
+impl RsgColor {
+    /// False for hex colors that are not made of 3 or 6 hex digits.
+    pub fn is_valid(self) -> bool {
+        match self {
+            RsgColor::Hex(text) => return normalize_hex(text).is_some(),
+            _ => return true
+        }
+    }
+}
+
+/// Returns a hex color as "#rrggbb" in lower case, or `None` if it is not
+/// made of 3 or 6 hex digits after an optional '#'.
+pub fn normalize_hex(text: &str) -> Option<String> {
+    let digits = text.trim().strip_prefix('#').unwrap_or(text.trim());
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match digits.len() {
+        3 => return Some(digits.chars().fold(String::from("#"), |hex, c| format!("{}{}{}", hex, c, c)).to_lowercase()),
+        6 => return Some(format!("#{}", digits.to_lowercase())),
+        _ => return None
+    }
+}
+
+/// Returns the Tk name of the color; invalid hex colors give an empty name.
 pub fn get_rsg_color(which: RsgColor) -> String {
     match which {
         RsgColor::Name => return String::from("Name"),
+        RsgColor::Hex(text) => return normalize_hex(text).unwrap_or_default(),
+        RsgColor::Rgb(r, g, b) => return format!("#{:02x}{:02x}{:02x}", r, g, b),
 RsgColor::Red => return String::from("Red"),
 RsgColor::Green => return String::from("Green"),
 RsgColor::Blue => return String::from("Blue"),
@@ -1536,4 +1566,21 @@ RsgColor::Yellowgreen => return String::from("YellowGreen"),
         _ => {}
     }
     return "".to_string()
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors_are_normalized() {
+        assert_eq!(Some(String::from("#ff8000")), normalize_hex("#FF8000"));
+        assert_eq!(Some(String::from("#ff8800")), normalize_hex("f80"));
+        assert_eq!(None, normalize_hex("#ff80"));
+        assert_eq!(None, normalize_hex("#ggg"));
+        assert_eq!(None, normalize_hex("red; exit"));
+        assert_eq!("#0a141e", get_rsg_color(RsgColor::Rgb(10, 20, 30)));
+        assert!(!RsgColor::Hex("nope").is_valid());
+    }
+}
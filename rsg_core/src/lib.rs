@@ -6,11 +6,18 @@
 #![allow(unused_mut)]
 
 pub use crate::colors::*;
+pub use crate::locale::*;
+pub use crate::options::*;
+pub use crate::values::*;
 
 mod colors;
+mod locale;
+mod options;
+mod values;
 
 
 #[derive(Clone)]
+#[derive(Debug)]
 pub enum RsgObjType {
     Text,
     Button,
@@ -18,7 +25,54 @@ pub enum RsgObjType {
     Radio,
     Input,
     Slider,
-    Separator
+    Separator,
+    Spin,
+    Progress,
+    Image,
+    Frame,
+    Column,
+    Kv,
+    Canvas,
+    Graph,
+    Output,
+    StatusBar,
+    Segmented,
+    FolderBrowse,
+    ColorChooser,
+    OptionMenu,
+    SizeGrip,
+    ScrolledText,
+    TitleBar,
+    ButtonMenu,
+    Paned,
+    RadioGroup,
+    Gauge,
+    NumberInput,
+    Checklist
+}
+
+/// Where an element sits in its grid cell, and how its text lines up.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum RsgJustify {
+    Left,
+    Center,
+    Right
+}
+
+/// Look of an element's border.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum RsgRelief {
+    Flat,
+    Raised,
+    Sunken,
+    Groove,
+    Ridge
 }
 
 #[derive(Clone)]
@@ -43,6 +97,45 @@ impl RsgOrientation {
     }
 }
 
+/// Font of an element. Attributes left at their default keep the element's
+/// usual font: an empty family keeps its family and a size of 0 its size.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct RsgFont {
+    pub family: &'static str,
+    /// Size in points.
+    pub size: u64,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool
+}
+
+// The cursors of the X cursor font, which Tk provides on every platform.
+const CURSORS: [&str; 78] = [
+    "X_cursor", "arrow", "based_arrow_down", "based_arrow_up", "boat", "bogosity", "bottom_left_corner",
+    "bottom_right_corner", "bottom_side", "bottom_tee", "box_spiral", "center_ptr", "circle", "clock",
+    "coffee_mug", "cross", "cross_reverse", "crosshair", "diamond_cross", "dot", "dotbox", "double_arrow",
+    "draft_large", "draft_small", "draped_box", "exchange", "fleur", "gobbler", "gumby", "hand1", "hand2",
+    "heart", "icon", "iron_cross", "left_ptr", "left_side", "left_tee", "leftbutton", "ll_angle", "lr_angle",
+    "man", "middlebutton", "mouse", "pencil", "pirate", "plus", "question_arrow", "right_ptr", "right_side",
+    "right_tee", "rightbutton", "rtl_logo", "sailboat", "sb_down_arrow", "sb_h_double_arrow", "sb_left_arrow",
+    "sb_right_arrow", "sb_up_arrow", "sb_v_double_arrow", "shuttle", "sizing", "spider", "spraycan", "star",
+    "target", "tcross", "top_left_arrow", "top_left_corner", "top_right_corner", "top_side", "top_tee", "trek",
+    "ul_angle", "umbrella", "ur_angle", "watch", "xterm", "none"
+];
+
+/// True for the cursor names Tk knows on every platform: those of the X
+/// cursor font, and "none" for no cursor at all.
+pub fn is_cursor(name: &str) -> bool {
+    return CURSORS.contains(&name);
+}
+
+/// Check of an element's value, true while the value is acceptable.
+pub type Check = std::sync::Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
 #[derive(Clone)]
 pub struct RsgObj {
     pub r#type: RsgObjType,
@@ -50,8 +143,111 @@ pub struct RsgObj {
     pub size: (u64, u64),
     pub color: (RsgColor, RsgColor),
     pub pad: (u64, u64),
-    pub range: (i64, u64)
+    pub range: (i64, u64),
+    pub y_range: (i64, u64),
+    pub layer: i32,
+    pub step: f64,
+    pub enable_events: bool,
+    pub editable: bool,
+    pub hide_in_compact: bool,
+    pub max_lines: u64,
+    pub track_changes: bool,
+    pub min_touch_size: Option<(u64, u64)>,
+    /// Key the element is addressed and reports events by, in place of its
+    /// name or generated key.
+    pub key: Option<String>,
+    pub font: Option<RsgFont>,
+    pub tooltip: Option<String>,
+    pub disabled: bool,
+    pub visible: bool,
+    pub justify: RsgJustify,
+    pub span: (u64, u64),
+    pub expand: (bool, bool),
+    pub border: Option<(u64, RsgRelief)>,
+    pub tick_interval: f64,
+    pub show_value: bool,
+    pub char_limit: Option<u64>,
+    pub bind_return_key: bool,
+    pub image: Option<String>,
+    pub cursor: Option<String>,
+    pub wrap: Option<u64>,
+    pub group: Option<String>,
+    pub tristate: bool,
+    pub focus: bool,
+    pub hover_color: (RsgColor, RsgColor),
+    /// Data of the caller's own kept with the element, see `set_metadata`.
+    pub metadata: Option<String>,
+    /// Check of the value run whenever the window reads values, see
+    /// `input_validated`.
+    pub check: Option<Check>,
+    /// Initial value shown by value-bearing elements.
+    pub value: String,
+    /// Choices offered by elements such as segmented bars.
+    pub options: Vec<String>,
+    /// Content given in memory, such as the encoded bytes of an image.
+    pub data: Vec<u8>,
+    /// Elements laid out inside this one, for containers such as frames.
+    pub layout: Vec<Vec<RsgObj>>
+}
 
+impl RsgObj {
+    pub fn new(r#type: RsgObjType, name: String, ex: RsgObjEx) -> RsgObj {
+        return RsgObj{
+            r#type,
+            name,
+            size: ex.size,
+            color: ex.color,
+            pad: ex.pad,
+            range: ex.range,
+            y_range: ex.y_range,
+            layer: ex.layer,
+            step: ex.step,
+            enable_events: ex.enable_events,
+            editable: ex.editable,
+            hide_in_compact: ex.hide_in_compact,
+            max_lines: ex.max_lines,
+            track_changes: ex.track_changes,
+            min_touch_size: ex.min_touch_size,
+            key: ex.key.map(String::from),
+            font: ex.font,
+            tooltip: ex.tooltip.map(String::from),
+            disabled: ex.disabled,
+            visible: ex.visible,
+            justify: ex.justify,
+            span: ex.span,
+            expand: ex.expand,
+            border: ex.border,
+            tick_interval: ex.tick_interval,
+            show_value: ex.show_value,
+            char_limit: ex.char_limit,
+            bind_return_key: ex.bind_return_key,
+            image: ex.image.map(String::from),
+            cursor: ex.cursor.map(String::from),
+            wrap: ex.wrap,
+            group: ex.group.map(String::from),
+            tristate: ex.tristate,
+            focus: ex.focus,
+            hover_color: ex.hover_color,
+            metadata: None,
+            check: None,
+            value: String::from(ex.default),
+            options: vec![],
+            data: vec![],
+            layout: vec![]
+        }
+    }
+
+    /// Sets the key the element is addressed and reports events by.
+    pub fn set_key(&mut self, key: impl Into<String>) {
+        self.key = Some(key.into());
+    }
+
+    /// Keeps `metadata`, such as the id of the record a button stands for,
+    /// with the element. The window hands it back through
+    /// `Window::metadata` and with the element's events.
+    pub fn set_metadata(&mut self, metadata: impl Into<String>) {
+        self.metadata = Some(metadata.into());
+    }
 }
 
 
@@ -60,8 +256,103 @@ pub struct RsgObj {
 pub struct RsgObjEx {
     pub size: (u64, u64),
     pub color: (RsgColor, RsgColor),
+    /// Space around the element. Left at the default, the window's
+    /// `element_gap` is used instead.
     pub pad: (u64, u64),
-    pub range: (i64, u64)
+    pub range: (i64, u64),
+    /// Second range, for elements with two axes such as graphs.
+    pub y_range: (i64, u64),
+    /// Stacking layer applied once the window is built: elements with a
+    /// higher layer are raised above their siblings, negative layers are
+    /// lowered beneath them. Elements on layer 0 keep creation order.
+    pub layer: i32,
+    /// Increment used by stepped elements, e.g. a spin's arrows. Sliders
+    /// round their value to it.
+    pub step: f64,
+    /// Report changes to the element's value as events from `read()`. On
+    /// buttons, report middle and right clicks as well as left ones; on
+    /// text elements, report clicks as buttons do.
+    pub enable_events: bool,
+    /// Lets the user edit a text element in place by clicking it.
+    pub editable: bool,
+    /// Hides the element while the window is in compact mode.
+    pub hide_in_compact: bool,
+    /// Lines an output element keeps before dropping the oldest; 0 keeps
+    /// everything.
+    pub max_lines: u64,
+    /// Counts changes to the element's value towards `Window::is_dirty()`.
+    pub track_changes: bool,
+    /// Smallest size in pixels the element is drawn at, grown evenly around
+    /// its content; `None` uses the window's `min_touch_size` for elements
+    /// that are clicked.
+    pub min_touch_size: Option<(u64, u64)>,
+    /// Key for `Window` methods, the keyed values and the events of `read()`,
+    /// instead of the visible text or a generated "<type>_<n>" key. Keys
+    /// already taken get a "_<n>" suffix.
+    pub key: Option<&'static str>,
+    /// Font of the element's text; `None` keeps the theme's font.
+    pub font: Option<RsgFont>,
+    /// Text shown next to the pointer while it rests on the element.
+    pub tooltip: Option<&'static str>,
+    /// Builds the element greyed out, as `Window::set_disabled` does.
+    pub disabled: bool,
+    /// Lays the element out; when false the element is built but kept out
+    /// of sight, holding its grid cell until `Window::set_visible` shows it.
+    pub visible: bool,
+    /// Value the element starts with: "true" checks a checkbox or selects a
+    /// radio in its group, a number sets a slider, a choice picks it in an
+    /// option menu. Empty keeps the element's usual start.
+    pub default: &'static str,
+    /// Aligns the element in its grid cell and the text of texts and inputs;
+    /// `Center` keeps the element centered as usual.
+    pub justify: RsgJustify,
+    /// Columns and rows of the grid the element covers, from its own cell
+    /// rightwards and downwards. Later elements whose cells it covers are
+    /// reported by `validate_layout` and left out.
+    pub span: (u64, u64),
+    /// Stretches the element to fill its cell horizontally and vertically,
+    /// and gives its column and row a share of the space the window gains
+    /// when resized.
+    pub expand: (bool, bool),
+    /// Border width in pixels and relief; `None` keeps the theme's border.
+    pub border: Option<(u64, RsgRelief)>,
+    /// Distance between the labelled ticks of a slider; 0 shows none.
+    pub tick_interval: f64,
+    /// Shows a slider's value next to it; the value is in `read()`'s values
+    /// either way.
+    pub show_value: bool,
+    /// Most characters an input takes; longer pastes are cut to fit.
+    pub char_limit: Option<u64>,
+    /// Clicks a button when Enter is pressed anywhere in its window, inputs
+    /// included. One button per window has the binding; if several ask for
+    /// it, the last one built gets it.
+    pub bind_return_key: bool,
+    /// Picture file a button shows left of its text, or alone when the text
+    /// is empty. A file that cannot be read leaves the text alone.
+    pub image: Option<&'static str>,
+    /// Name of the mouse cursor shown over the element, such as "hand2",
+    /// "watch" or "xterm"; see [`is_cursor`]. Unknown names are reported by
+    /// `validate_layout` and left out.
+    pub cursor: Option<&'static str>,
+    /// Width in pixels past which a text element breaks its lines, on top
+    /// of the line breaks in the text itself. With `expand.0` the text
+    /// re-wraps to the width it is given when the window is resized.
+    pub wrap: Option<u64>,
+    /// Name of the group a radio belongs to: one radio of a group is
+    /// selected at a time. `None` groups the radios of one layout row.
+    pub group: Option<&'static str>,
+    /// Gives a checkbox a third, "mixed" state besides checked and not,
+    /// e.g. for a "select all" box over a partly selected list. It starts
+    /// mixed with `default: "mixed"` or through `Window::update_checkbox`;
+    /// clicks only check and uncheck it.
+    pub tristate: bool,
+    /// Gives the element the keyboard focus when the window opens; if
+    /// several ask for it, the last one built gets it.
+    pub focus: bool,
+    /// Text and background colors while the pointer is on the element or
+    /// it is pressed. `RsgColor::None` keeps the active colors derived from
+    /// `color`.
+    pub hover_color: (RsgColor, RsgColor)
 }
 
 impl Default for RsgObjEx {
@@ -70,7 +361,489 @@ impl Default for RsgObjEx {
             size: (0, 0),
             color: (RsgColor::None, RsgColor::None),
             pad: (10, 4),
-            range: (0, 100)
+            range: (0, 100),
+            y_range: (0, 100),
+            layer: 0,
+            step: 1.0,
+            enable_events: false,
+            editable: false,
+            hide_in_compact: false,
+            max_lines: 1000,
+            track_changes: true,
+            min_touch_size: None,
+            key: None,
+            font: None,
+            tooltip: None,
+            disabled: false,
+            visible: true,
+            default: "",
+            justify: RsgJustify::Center,
+            span: (1, 1),
+            expand: (false, false),
+            border: None,
+            tick_interval: 0.0,
+            show_value: true,
+            char_limit: None,
+            bind_return_key: false,
+            image: None,
+            cursor: None,
+            wrap: None,
+            group: None,
+            tristate: false,
+            focus: false,
+            hover_color: (RsgColor::None, RsgColor::None)
+        }
+    }
+}
+
+/// Chainable setters, e.g.
+/// `RsgObjEx::new().size(10, 2).fg(RsgColor::Red).bg(RsgColor::Black)`.
+/// Each returns the options with one field changed, starting from
+/// `RsgObjEx::default()`.
+impl RsgObjEx {
+    pub fn new() -> RsgObjEx {
+        return RsgObjEx::default();
+    }
+
+    pub fn size(self, width: u64, height: u64) -> RsgObjEx {
+        return RsgObjEx{ size: (width, height), ..self };
+    }
+
+    /// Sets the text color, the first of `color`.
+    pub fn fg(self, fg: RsgColor) -> RsgObjEx {
+        return RsgObjEx{ color: (fg, self.color.1), ..self };
+    }
+
+    /// Sets the background color, the second of `color`.
+    pub fn bg(self, bg: RsgColor) -> RsgObjEx {
+        return RsgObjEx{ color: (self.color.0, bg), ..self };
+    }
+
+    /// Sets the text and background colors shown on hover, `hover_color`.
+    pub fn hover(self, fg: RsgColor, bg: RsgColor) -> RsgObjEx {
+        return RsgObjEx{ hover_color: (fg, bg), ..self };
+    }
+
+    pub fn pad(self, x: u64, y: u64) -> RsgObjEx {
+        return RsgObjEx{ pad: (x, y), ..self };
+    }
+
+    pub fn range(self, min: i64, max: u64) -> RsgObjEx {
+        return RsgObjEx{ range: (min, max), ..self };
+    }
+
+    pub fn y_range(self, min: i64, max: u64) -> RsgObjEx {
+        return RsgObjEx{ y_range: (min, max), ..self };
+    }
+
+    pub fn layer(self, layer: i32) -> RsgObjEx {
+        return RsgObjEx{ layer, ..self };
+    }
+
+    pub fn step(self, step: f64) -> RsgObjEx {
+        return RsgObjEx{ step, ..self };
+    }
+
+    pub fn enable_events(self) -> RsgObjEx {
+        return RsgObjEx{ enable_events: true, ..self };
+    }
+
+    pub fn editable(self) -> RsgObjEx {
+        return RsgObjEx{ editable: true, ..self };
+    }
+
+    pub fn hide_in_compact(self) -> RsgObjEx {
+        return RsgObjEx{ hide_in_compact: true, ..self };
+    }
+
+    pub fn max_lines(self, max_lines: u64) -> RsgObjEx {
+        return RsgObjEx{ max_lines, ..self };
+    }
+
+    /// Sets `track_changes`, which is on by default.
+    pub fn track_changes(self, track_changes: bool) -> RsgObjEx {
+        return RsgObjEx{ track_changes, ..self };
+    }
+
+    pub fn min_touch_size(self, width: u64, height: u64) -> RsgObjEx {
+        return RsgObjEx{ min_touch_size: Some((width, height)), ..self };
+    }
+
+    pub fn key(self, key: &'static str) -> RsgObjEx {
+        return RsgObjEx{ key: Some(key), ..self };
+    }
+
+    pub fn font(self, font: RsgFont) -> RsgObjEx {
+        return RsgObjEx{ font: Some(font), ..self };
+    }
+
+    pub fn tooltip(self, tooltip: &'static str) -> RsgObjEx {
+        return RsgObjEx{ tooltip: Some(tooltip), ..self };
+    }
+
+    pub fn disabled(self) -> RsgObjEx {
+        return RsgObjEx{ disabled: true, ..self };
+    }
+
+    pub fn hidden(self) -> RsgObjEx {
+        return RsgObjEx{ visible: false, ..self };
+    }
+
+    /// Sets `default`, the value the element starts with.
+    pub fn default_value(self, default: &'static str) -> RsgObjEx {
+        return RsgObjEx{ default, ..self };
+    }
+
+    pub fn justify(self, justify: RsgJustify) -> RsgObjEx {
+        return RsgObjEx{ justify, ..self };
+    }
+
+    pub fn span(self, columns: u64, rows: u64) -> RsgObjEx {
+        return RsgObjEx{ span: (columns, rows), ..self };
+    }
+
+    pub fn expand(self, x: bool, y: bool) -> RsgObjEx {
+        return RsgObjEx{ expand: (x, y), ..self };
+    }
+
+    pub fn border(self, width: u64, relief: RsgRelief) -> RsgObjEx {
+        return RsgObjEx{ border: Some((width, relief)), ..self };
+    }
+
+    pub fn tick_interval(self, tick_interval: f64) -> RsgObjEx {
+        return RsgObjEx{ tick_interval, ..self };
+    }
+
+    /// Sets `show_value`, which is on by default.
+    pub fn show_value(self, show_value: bool) -> RsgObjEx {
+        return RsgObjEx{ show_value, ..self };
+    }
+
+    pub fn char_limit(self, char_limit: u64) -> RsgObjEx {
+        return RsgObjEx{ char_limit: Some(char_limit), ..self };
+    }
+
+    pub fn bind_return_key(self) -> RsgObjEx {
+        return RsgObjEx{ bind_return_key: true, ..self };
+    }
+
+    pub fn image(self, image: &'static str) -> RsgObjEx {
+        return RsgObjEx{ image: Some(image), ..self };
+    }
+
+    pub fn cursor(self, cursor: &'static str) -> RsgObjEx {
+        return RsgObjEx{ cursor: Some(cursor), ..self };
+    }
+
+    pub fn wrap(self, width: u64) -> RsgObjEx {
+        return RsgObjEx{ wrap: Some(width), ..self };
+    }
+
+    pub fn group(self, group: &'static str) -> RsgObjEx {
+        return RsgObjEx{ group: Some(group), ..self };
+    }
+
+    pub fn tristate(self) -> RsgObjEx {
+        return RsgObjEx{ tristate: true, ..self };
+    }
+
+    pub fn focus(self) -> RsgObjEx {
+        return RsgObjEx{ focus: true, ..self };
+    }
+}
+
+/// Window-wide options, see `window_ex()`.
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct RsgWindowEx {
+    /// Space between the window's edges and its content.
+    pub margin: (u64, u64),
+    /// Pad of every element left at the default pad.
+    pub element_gap: (u64, u64),
+    /// Configure only the fonts and colors set on elements, leaving the rest
+    /// to Tk's option database (e.g. Xresources) instead of deriving them.
+    pub respect_option_db: bool,
+    /// Locks the window down for unattended displays: fullscreen, above
+    /// other windows, ignoring close requests and hiding the mouse cursor
+    /// while it is idle.
+    pub kiosk: bool,
+    /// Keys typed anywhere in a kiosk window to close it, e.g. "unlock!";
+    /// empty means only the program can close it.
+    pub kiosk_unlock: &'static str,
+    /// Seconds without mouse movement before a kiosk window hides the
+    /// cursor; 0 never hides it.
+    pub kiosk_cursor_idle: u64,
+    /// Smallest size in pixels of every element that is clicked, such as
+    /// buttons and checkboxes, unless it sets its own.
+    pub min_touch_size: Option<(u64, u64)>,
+    /// Factor applied to the standard fonts when the window is created.
+    pub font_scale: f64,
+    /// Width and height in pixels the window opens at; `None` fits it to
+    /// its content.
+    pub size: Option<(u64, u64)>,
+    /// Screen position the window opens at, in pixels from the left and top
+    /// edges of the screen; negative values are from the right and bottom
+    /// edges instead, to the window's own right and bottom edges, as with
+    /// Tk's `wm geometry`; (-1, 0) is one pixel from the top-right corner.
+    /// `None` lets the window manager place it.
+    pub location: Option<(i64, i64)>,
+    /// Lets the user resize the window horizontally and vertically. The
+    /// space gained goes to the elements with `expand` set, and to the
+    /// frames and columns holding them.
+    pub resizable: (bool, bool),
+    /// Smallest width and height in pixels the user can shrink the window to.
+    pub min_size: Option<(u64, u64)>,
+    /// Largest width and height in pixels the user can grow the window to.
+    pub max_size: Option<(u64, u64)>,
+    /// Keeps the window above other windows, e.g. for a tool palette.
+    pub keep_on_top: bool,
+    /// Picture file shown as the window's icon: a PNG or GIF, or an ICO file
+    /// on Windows. An icon that cannot be loaded leaves Tk's own, with a
+    /// warning.
+    pub icon: Option<&'static str>,
+    /// Encoded icon picture in memory, e.g. from `include_bytes!`; used
+    /// instead of `icon` when not empty.
+    pub icon_data: &'static [u8]
+}
+
+impl Default for RsgWindowEx {
+    fn default() -> RsgWindowEx {
+        return RsgWindowEx{
+            margin: (0, 0),
+            element_gap: (10, 4),
+            respect_option_db: true,
+            kiosk: false,
+            kiosk_unlock: "",
+            kiosk_cursor_idle: 5,
+            min_touch_size: None,
+            font_scale: 1.0,
+            size: None,
+            location: None,
+            resizable: (false, false),
+            min_size: None,
+            max_size: None,
+            keep_on_top: false,
+            icon: None,
+            icon_data: &[]
+        }
+    }
+}
+
+impl RsgWindowEx {
+    /// Preset for touch screens: larger click targets, pads and fonts.
+    pub fn touch_mode() -> RsgWindowEx {
+        return RsgWindowEx{
+            element_gap: (16, 8),
+            min_touch_size: Some((48, 48)),
+            font_scale: 1.4,
+            ..RsgWindowEx::default()
+        }
+    }
+}
+
+/// A value reported by an element, as text with typed accessors.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct RsgValue(pub String);
+
+impl RsgValue {
+    pub fn as_str(&self) -> &str {
+        return &self.0;
+    }
+
+    /// The value as a number, accepting the locale's decimal separator as
+    /// well as '.', see `set_decimal_separator`.
+    pub fn as_f64(&self) -> Option<f64> {
+        return parse_decimal(&self.0);
+    }
+
+    /// The value as a boolean, from "true"/"false" or "1"/"0".
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.0.trim() {
+            "true" | "1" => return Some(true),
+            "false" | "0" => return Some(false),
+            _ => return None
+        }
+    }
+}
+
+impl std::fmt::Display for RsgValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.0);
+    }
+}
+
+impl From<String> for RsgValue {
+    fn from(value: String) -> RsgValue {
+        return RsgValue(value);
+    }
+}
+
+/// What kind of entry a [`RsgMenuItem`] is.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum RsgMenuKind {
+    Command,
+    /// A checkable item, checked to begin with or not.
+    Check(bool),
+    /// An item of the named group, of which one item is chosen at a time.
+    Radio(String),
+    Separator,
+    Submenu
+}
+
+/// An entry of the menu bar. Items are addressed by their path, the labels
+/// from the menu bar down joined with "/", e.g. "File/Save", unless given a
+/// key.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct RsgMenuItem {
+    pub kind: RsgMenuKind,
+    pub label: String,
+    /// Key the item is addressed by and reported under instead of its path,
+    /// if not empty.
+    pub key: String,
+    /// Shortcut shown next to the label and bound on the window, such as
+    /// "Ctrl+S".
+    pub accelerator: String,
+    /// Entries of a submenu.
+    pub items: Vec<RsgMenuItem>
+}
+
+impl RsgMenuItem {
+    pub fn new(kind: RsgMenuKind, label: String) -> RsgMenuItem {
+        return RsgMenuItem{ kind, label, key: String::new(), accelerator: String::new(), items: vec![] };
+    }
+}
+
+/// An event reported by `Window::read()`, as passed to event hooks.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct RsgEvent {
+    /// Key of the element the event came from.
+    pub key: String,
+    /// The event as `read()` returns it, e.g. "Ok" or "Hello:::true".
+    pub event: String,
+    /// The new value, for events reporting a change.
+    pub value: Option<String>,
+    /// Mouse button that clicked a button element: 1 for left, 2 for
+    /// middle and 3 for right. Events not coming from a mouse report 1.
+    pub mouse_button: u8,
+    /// Modifier keys held when the button element was clicked.
+    pub modifiers: RsgModifiers,
+    /// Metadata set on the element, see `RsgObj::set_metadata`.
+    pub metadata: Option<String>
+}
+
+/// Modifier keys held during a click.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct RsgModifiers {
+    pub shift: bool,
+    pub ctrl: bool
+}
+
+impl RsgModifiers {
+    /// Reads the modifiers from a Tk event state (`%s`).
+    pub fn from_tk_state(state: u32) -> RsgModifiers {
+        return RsgModifiers{ shift: state & 1 != 0, ctrl: state & 4 != 0 };
+    }
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub enum RsgError {
+    /// No element in the window is registered under the given key.
+    UnknownKey(String),
+    /// The layout given to a strict window has problems.
+    InvalidLayout(Vec<LayoutIssue>),
+    /// A native dialog is open, so wish cannot answer queries.
+    Busy,
+    /// The element with the given key does not support the operation.
+    WrongElementType(String),
+    /// Tk could not read image data, with Tk's explanation.
+    ImageLoad(String)
+}
+
+impl std::fmt::Display for RsgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            RsgError::UnknownKey(key) => return write!(f, "no element with key \"{}\"", key),
+            RsgError::Busy => return write!(f, "a native dialog is open"),
+            RsgError::WrongElementType(key) => return write!(f, "element \"{}\" does not support this operation", key),
+            RsgError::ImageLoad(message) => return write!(f, "could not load image: {}", message),
+            RsgError::InvalidLayout(issues) => {
+                write!(f, "invalid layout:")?;
+                for issue in issues {
+                    write!(f, "\n  {}", issue)?;
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl std::error::Error for RsgError {}
+
+/// A problem found in a layout before any widget is created.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct LayoutIssue {
+    /// Row of the element within the layout that holds it.
+    pub row: usize,
+    /// Column of the element within that row.
+    pub column: usize,
+    /// Key of the enclosing container, or `None` at the top level.
+    pub container: Option<String>,
+    pub kind: LayoutIssueKind
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum LayoutIssueKind {
+    /// The element's key was already taken, so it was keyed `assigned`.
+    DuplicateKey{ key: String, assigned: String },
+    /// An image with only one dimension of its size set; the image is shown
+    /// at its natural size instead.
+    ZeroSizeImage,
+    /// A color was set on an element type that does not show colors.
+    IgnoredColor,
+    /// A hex color that is not made of 3 or 6 hex digits; the element is
+    /// built without it.
+    InvalidColor{ color: String },
+    /// A cursor name Tk does not know, see `is_cursor`; the element is built
+    /// without it.
+    InvalidCursor{ cursor: String },
+    /// A radio group with nothing to choose between: a radio group element
+    /// without options, or a `group` name no other radio uses.
+    EmptyRadioGroup{ group: String },
+    /// The element's cells overlap those of an element spanning over them
+    /// earlier in the layout; the element is left out.
+    SpanCollision
+}
+
+impl std::fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "row {}, column {}", self.row, self.column)?;
+        if let Some(container) = &self.container {
+            write!(f, " of \"{}\"", container)?;
+        }
+        match &self.kind {
+            LayoutIssueKind::DuplicateKey{ key, assigned } =>
+                return write!(f, ": key \"{}\" is already used, keyed \"{}\" instead", key, assigned),
+            LayoutIssueKind::ZeroSizeImage => return write!(f, ": image size has a zero dimension"),
+            LayoutIssueKind::IgnoredColor => return write!(f, ": color is ignored by this element type"),
+            LayoutIssueKind::InvalidColor{ color } => return write!(f, ": \"{}\" is not a hex color", color),
+            LayoutIssueKind::InvalidCursor{ cursor } => return write!(f, ": \"{}\" is not a cursor name", cursor),
+            LayoutIssueKind::EmptyRadioGroup{ group } => return write!(f, ": radio group \"{}\" has nothing to choose between", group),
+            LayoutIssueKind::SpanCollision => return write!(f, ": cell is covered by a spanning element, left out"),
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,131 @@
+// Decimal separators for numbers typed into and shown by elements.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// The separator as a char, or 0 until it is set or detected.
+static DECIMAL_SEPARATOR: AtomicU32 = AtomicU32::new(0);
+
+// Languages writing "3,5" for three and a half.
+const COMMA_LANGUAGES: [&str; 24] = [
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu",
+    "id", "it", "lt", "lv", "nb", "nl", "pl", "pt", "ro", "ru", "sv", "tr"
+];
+
+/// Sets the decimal separator used to parse and format numbers, instead of
+/// the one detected from the environment.
+pub fn set_decimal_separator(separator: char) {
+    DECIMAL_SEPARATOR.store(separator as u32, Ordering::SeqCst);
+}
+
+/// The decimal separator in use: the one given to `set_decimal_separator`,
+/// or else ',' when `LC_ALL`, `LC_NUMERIC` or `LANG` names a language
+/// writing decimals that way, and '.' otherwise.
+pub fn decimal_separator() -> char {
+    let stored = DECIMAL_SEPARATOR.load(Ordering::SeqCst);
+    if stored != 0 {
+        return char::from_u32(stored).unwrap_or('.');
+    }
+    let detected = detect_decimal_separator();
+    DECIMAL_SEPARATOR.store(detected as u32, Ordering::SeqCst);
+    return detected;
+}
+
+fn detect_decimal_separator() -> char {
+    for var in ["LC_ALL", "LC_NUMERIC", "LANG"] {
+        if let Ok(locale) = std::env::var(var) {
+            if locale.is_empty() { continue; }
+            return separator_for_locale(&locale);
+        }
+    }
+    return '.';
+}
+
+fn separator_for_locale(locale: &str) -> char {
+    let language = locale.split(['_', '-', '.']).next().unwrap_or("");
+    return if COMMA_LANGUAGES.contains(&language) { ',' } else { '.' };
+}
+
+/// Parses a number typed with the current decimal separator, see
+/// [`parse_decimal_with`].
+pub fn parse_decimal(text: &str) -> Option<f64> {
+    return parse_decimal_with(text, decimal_separator());
+}
+
+/// Parses a number written with either '.' or `separator` as decimal
+/// separator. When both appear, as in "1.234,56", the last one is the
+/// decimal separator and the other one groups thousands. Spaces group
+/// thousands too.
+pub fn parse_decimal_with(text: &str, separator: char) -> Option<f64> {
+    let text: String = text.trim().chars().filter(|c| !matches!(c, ' ' | '\u{a0}' | '\u{202f}')).collect();
+    let last_dot = text.rfind('.');
+    let last_separator = text.rfind(separator);
+
+    let (decimal, grouping) = match (last_dot, last_separator) {
+        (Some(dot), Some(sep)) if separator != '.' => {
+            if sep > dot { (separator, Some('.')) } else { ('.', Some(separator)) }
+        }
+        (None, Some(_)) => (separator, None),
+        _ => ('.', None)
+    };
+
+    let mut normalized = String::new();
+    for c in text.chars() {
+        if Some(c) == grouping {
+            continue;
+        }
+        normalized.push(if c == decimal { '.' } else { c });
+    }
+    return normalized.parse::<f64>().ok();
+}
+
+/// Formats a number with the current decimal separator, with `precision`
+/// decimals if given.
+pub fn format_decimal(value: f64, precision: Option<usize>) -> String {
+    return format_decimal_with(value, precision, decimal_separator());
+}
+
+pub fn format_decimal_with(value: f64, precision: Option<usize>, separator: char) -> String {
+    let text = match precision {
+        Some(precision) => format!("{:.*}", precision, value),
+        None => value.to_string()
+    };
+    return text.replace('.', &separator.to_string());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comma_and_dot_are_both_accepted() {
+        assert_eq!(Some(3.5), parse_decimal_with("3,5", ','));
+        assert_eq!(Some(3.5), parse_decimal_with("3.5", ','));
+        assert_eq!(Some(3.5), parse_decimal_with("3.5", '.'));
+        assert_eq!(None, parse_decimal_with("3,5", '.'));
+    }
+
+    #[test]
+    fn mixed_separators_group_thousands() {
+        assert_eq!(Some(1234.56), parse_decimal_with("1.234,56", ','));
+        assert_eq!(Some(1234.56), parse_decimal_with("1,234.56", ','));
+        assert_eq!(Some(-1234567.5), parse_decimal_with(" -1 234 567,5 ", ','));
+        assert_eq!(None, parse_decimal_with("1,2,3", ','));
+        assert_eq!(None, parse_decimal_with("", ','));
+    }
+
+    #[test]
+    fn formatting_uses_the_separator() {
+        assert_eq!("3,5", format_decimal_with(3.5, None, ','));
+        assert_eq!("1234,560", format_decimal_with(1234.56, Some(3), ','));
+        assert_eq!("0.25", format_decimal_with(0.25, None, '.'));
+    }
+
+    #[test]
+    fn locales_map_to_separators() {
+        assert_eq!(',', separator_for_locale("de_DE.UTF-8"));
+        assert_eq!(',', separator_for_locale("fr"));
+        assert_eq!('.', separator_for_locale("en_US.UTF-8"));
+        assert_eq!('.', separator_for_locale("C"));
+    }
+}
@@ -0,0 +1,70 @@
+// Defaults every window built afterwards applies, see `set_options`.
+
+use std::sync::Mutex;
+
+/// Sizing defaults for the elements of windows, set with [`set_options`].
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct RsgOptions {
+    /// Sizes texts without a `size` to fit their text; when false they get
+    /// `default_element_size`.
+    pub auto_size_text: bool,
+    /// Sizes buttons without a `size` to fit their text; when false they get
+    /// `default_element_size`.
+    pub auto_size_buttons: bool,
+    /// Width and height in characters of the elements not sized to fit,
+    /// 0 leaving that dimension to fit.
+    pub default_element_size: (u64, u64)
+}
+
+static OPTIONS: Mutex<RsgOptions> = Mutex::new(RsgOptions{
+    auto_size_text: true,
+    auto_size_buttons: true,
+    default_element_size: (0, 0)
+});
+
+/// Sets the sizing defaults of the windows built from now on, e.g.
+/// `set_options(true, false, (12, 1))` to give every button without a
+/// `size` the same width of 12 characters.
+pub fn set_options(auto_size_text: bool, auto_size_buttons: bool, default_element_size: (u64, u64)) {
+    *OPTIONS.lock().unwrap() = RsgOptions{
+        auto_size_text,
+        auto_size_buttons,
+        default_element_size
+    };
+}
+
+/// The sizing defaults in use, see [`set_options`].
+pub fn options() -> RsgOptions {
+    return *OPTIONS.lock().unwrap();
+}
+
+impl RsgOptions {
+    /// The size in characters of an element given `size`: its nonzero
+    /// dimensions, the others taken from `default_element_size` unless the
+    /// element sizes to fit.
+    pub fn element_size(&self, size: (u64, u64), auto_size: bool) -> (u64, u64) {
+        if auto_size {
+            return size;
+        }
+        let pick = |given: u64, default: u64| if given != 0 { given } else { default };
+        return (pick(size.0, self.default_element_size.0), pick(size.1, self.default_element_size.1));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_sizes_win_over_the_default() {
+        let options = RsgOptions{ auto_size_text: true, auto_size_buttons: false, default_element_size: (12, 1) };
+        assert_eq!((0, 0), options.element_size((0, 0), options.auto_size_text));
+        assert_eq!((12, 1), options.element_size((0, 0), options.auto_size_buttons));
+        assert_eq!((20, 1), options.element_size((20, 0), options.auto_size_buttons));
+        assert_eq!((20, 3), options.element_size((20, 3), options.auto_size_buttons));
+    }
+}
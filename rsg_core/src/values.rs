@@ -0,0 +1,94 @@
+// Keyed element values as returned by `Window::read_values()`.
+
+use crate::RsgValue;
+
+/// The values of a window's elements, keyed by element key in layout order.
+///
+/// Prefer looking values up by key over indexing `read()`'s list: adding an
+/// element shifts the positions of the values after it, but not their keys.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct Values {
+    entries: Vec<(String, RsgValue)>
+}
+
+impl Values {
+    pub fn new(entries: Vec<(String, RsgValue)>) -> Values {
+        return Values{ entries };
+    }
+
+    pub fn get(&self, key: &str) -> Option<&RsgValue> {
+        return self.entries.iter().find(|(k, _)| k == key).map(|(_, value)| value);
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        return self.get(key).map(|value| value.as_str());
+    }
+
+    /// The value as a number, see [`RsgValue::as_f64`].
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        return self.get(key).and_then(|value| value.as_f64());
+    }
+
+    /// The value as a boolean, from "true"/"false" or "1"/"0".
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        return self.get(key).and_then(|value| value.as_bool());
+    }
+
+    /// Same as [`Values::get`], but panics with the available keys when
+    /// there is no value for `key`.
+    pub fn expect(&self, key: &str) -> &RsgValue {
+        match self.get(key) {
+            Some(value) => return value,
+            None => panic!("no value for key \"{}\"; available keys: {}", key, self.keys().join(", "))
+        }
+    }
+
+    /// Keys with a value, in layout order.
+    pub fn keys(&self) -> Vec<&str> {
+        return self.entries.iter().map(|(key, _)| key.as_str()).collect();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &RsgValue)> {
+        return self.entries.iter().map(|(key, value)| (key.as_str(), value));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Values {
+        return Values::new(vec![
+            (String::from("input_0"), RsgValue::from(String::from("Ferris"))),
+            (String::from("slider_0"), RsgValue::from(String::from("42"))),
+            (String::from("flag"), RsgValue::from(String::from("true")))
+        ]);
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let values = values();
+        assert_eq!(Some("Ferris"), values.get_str("input_0"));
+        assert_eq!(Some(42.0), values.get_f64("slider_0"));
+        assert_eq!(None, values.get_f64("input_0"));
+        assert_eq!(Some(true), values.get_bool("flag"));
+        assert_eq!(None, values.get("input_1"));
+    }
+
+    #[test]
+    #[should_panic(expected = "no value for key \"input_1\"; available keys: input_0, slider_0, flag")]
+    fn expect_lists_the_keys() {
+        values().expect("input_1");
+    }
+}
@@ -0,0 +1,75 @@
+// Drawing on canvas elements.
+
+use rsg_core::{get_rsg_color, RsgColor};
+
+/// Draws on a [`canvas`](crate::canvas) element, obtained with
+/// [`Window::canvas`](crate::Window::canvas).
+///
+/// Coordinates are in pixels from the canvas' top left corner. Drawings stay
+/// until [`RsgCanvas::clear`] is called, so a canvas redrawn after every
+/// `read()` should be cleared first.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct RsgCanvas {
+    id: String
+}
+
+impl RsgCanvas {
+    pub(crate) fn new(id: &str) -> RsgCanvas {
+        return RsgCanvas{ id: id.to_string() };
+    }
+
+    pub fn draw_line(&self, from: (f64, f64), to: (f64, f64), color: RsgColor) {
+        rstk::tell_wish(&line_command(&self.id, from, to, color));
+    }
+
+    /// Draws a rectangle between two opposite corners. `RsgColor::None`
+    /// leaves the inside or the outline transparent.
+    pub fn draw_rect(&self, from: (f64, f64), to: (f64, f64), fill: RsgColor, outline: RsgColor) {
+        rstk::tell_wish(&shape_command(&self.id, "rectangle", from, to, fill, outline));
+    }
+
+    /// Draws the oval fitting in the rectangle between two opposite corners.
+    pub fn draw_oval(&self, from: (f64, f64), to: (f64, f64), fill: RsgColor, outline: RsgColor) {
+        rstk::tell_wish(&shape_command(&self.id, "oval", from, to, fill, outline));
+    }
+
+    /// Draws text centred on `at`.
+    pub fn draw_text(&self, at: (f64, f64), text: &str, color: RsgColor) {
+        rstk::tell_wish(&text_command(&self.id, at, text, color));
+    }
+
+    /// Removes everything drawn so far.
+    pub fn clear(&self) {
+        rstk::tell_wish(&format!("{} delete all", self.id));
+    }
+}
+
+fn line_command(id: &str, from: (f64, f64), to: (f64, f64), color: RsgColor) -> String {
+    return format!("{} create line {} {} {} {} -fill {{{}}}", id, from.0, from.1, to.0, to.1, get_rsg_color(color));
+}
+
+fn shape_command(id: &str, shape: &str, from: (f64, f64), to: (f64, f64), fill: RsgColor, outline: RsgColor) -> String {
+    return format!("{} create {} {} {} {} {} -fill {{{}}} -outline {{{}}}",
+                   id, shape, from.0, from.1, to.0, to.1, get_rsg_color(fill), get_rsg_color(outline));
+}
+
+fn text_command(id: &str, at: (f64, f64), text: &str, color: RsgColor) -> String {
+    return format!("{} create text {} {} -text {} -fill {{{}}}", id, at.0, at.1, crate::tcl_string(text), get_rsg_color(color));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drawing_commands() {
+        assert_eq!(".r1 create line 0 0 10.5 20 -fill {violet red}",
+                   line_command(".r1", (0.0, 0.0), (10.5, 20.0), RsgColor::VioletRed));
+        assert_eq!(".r1 create oval 1 2 3 4 -fill {} -outline {black}",
+                   shape_command(".r1", "oval", (1.0, 2.0), (3.0, 4.0), RsgColor::None, RsgColor::Black));
+        assert_eq!(".r1 create text 5 6 -text \"\\[x\\]\" -fill {}",
+                   text_command(".r1", (5.0, 6.0), "[x]", RsgColor::None));
+    }
+}
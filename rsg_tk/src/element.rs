@@ -0,0 +1,70 @@
+// Handles to single elements of a window.
+
+use rsg_core::{RsgColor, RsgError};
+
+use crate::Window;
+
+/// Updates one element of a [`Window`], obtained with
+/// [`Window::element`](crate::Window::element).
+///
+/// The handle is usable as soon as `window()` returns, before the first
+/// `read()`, so elements can be set up from code without knowing their Tk
+/// ids. It only holds the element's key and Tk id: each method takes the
+/// window and does what the `Window` method of the same name does with the
+/// key. Once the element is gone, e.g. after [`Window::swap_layout`], the
+/// methods fail with [`RsgError::UnknownKey`].
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct RsgElement {
+    key: String,
+    id: String
+}
+
+impl RsgElement {
+    pub(crate) fn new(key: &str, id: &str) -> RsgElement {
+        return RsgElement{ key: key.to_string(), id: id.to_string() };
+    }
+
+    pub fn key(&self) -> &str {
+        return &self.key;
+    }
+
+    /// Returns the Tk path of the element, for use with the low-level
+    /// `rstk::tell_wish` API.
+    pub fn widget_id(&self, window: &Window) -> Result<&str, RsgError> {
+        if window.widget_id(&self.key)? != self.id {
+            return Err(RsgError::UnknownKey(self.key.clone()));
+        }
+        return Ok(&self.id);
+    }
+
+    /// See [`Window::update_text`].
+    pub fn update_text(&self, window: &mut Window, text: &str) -> Result<(), RsgError> {
+        self.widget_id(window)?;
+        return window.update_text(&self.key, text);
+    }
+
+    /// See [`Window::set_disabled`].
+    pub fn set_disabled(&self, window: &Window, disabled: bool) -> Result<(), RsgError> {
+        self.widget_id(window)?;
+        return window.set_disabled(&self.key, disabled);
+    }
+
+    /// See [`Window::set_visible`].
+    pub fn set_visible(&self, window: &mut Window, visible: bool) -> Result<(), RsgError> {
+        self.widget_id(window)?;
+        return window.set_visible(&self.key, visible);
+    }
+
+    /// See [`Window::set_colors`].
+    pub fn set_colors(&self, window: &mut Window, color: (RsgColor, RsgColor)) -> Result<(), RsgError> {
+        self.widget_id(window)?;
+        return window.set_colors(&self.key, color);
+    }
+
+    /// See [`Window::set_value`].
+    pub fn set_value(&self, window: &Window, value: &str) -> Result<(), RsgError> {
+        self.widget_id(window)?;
+        return window.set_value(&self.key, value);
+    }
+}
@@ -0,0 +1,325 @@
+// Thread-safe handle for updating a window from other threads.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rsg_core::RsgError;
+
+/// Rate at which coalesced updates are sent to wish unless changed with
+/// [`WindowHandle::set_max_rate`].
+pub const DEFAULT_MAX_RATE: f64 = 30.0;
+
+/// A cloneable, `Send + Sync` handle to a [`Window`](crate::Window), obtained
+/// with [`Window::handle`](crate::Window::handle).
+///
+/// Updates do not reach wish right away: successive updates to the same
+/// element collapse to the latest value, and the pending values are sent at
+/// most [`DEFAULT_MAX_RATE`] times a second. Call [`WindowHandle::flush`] to
+/// send them immediately, e.g. for the final values of a feed.
+#[derive(Clone)]
+pub struct WindowHandle {
+    shared: Arc<Shared>
+}
+
+// What a handle needs to know about one element.
+pub(crate) struct HandleElement {
+    pub(crate) id: String,
+    // the entry showing the value of a kv row
+    pub(crate) kv_entry: Option<String>,
+    pub(crate) gauge: Option<crate::Gauge>,
+    // Tcl returning the element's value, for value-bearing elements
+    pub(crate) query: Option<String>,
+    // whether the value is the text of a number input, see `number_input_value`
+    pub(crate) number_input: bool
+}
+
+struct Shared {
+    elements: HashMap<String, HandleElement>,
+    pending: Mutex<Pending>,
+    interval: Mutex<Duration>,
+    events: Arc<EventQueue>
+}
+
+// Set while a native modal dialog is open. wish answers nothing else until
+// the dialog closes, and whatever it prints meanwhile would be taken as the
+// dialog's answer.
+static MODAL: AtomicBool = AtomicBool::new(false);
+
+fn modal() -> bool {
+    return MODAL.load(Ordering::SeqCst);
+}
+
+// Tells `read()` an injected event is waiting.
+const WAKE: &str = "puts injected ; flush stdout";
+
+// Events injected from other threads, oldest first, with the time they were
+// injected. `read()` is woken through wish once per event.
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    events: Mutex<VecDeque<(Instant, String)>>,
+    stale_after: Mutex<Option<Duration>>
+}
+
+impl EventQueue {
+    fn push(&self, at: Instant, id: String) {
+        self.events.lock().unwrap().push_back((at, id));
+    }
+
+    pub(crate) fn pop(&self) -> Option<String> {
+        return self.events.lock().unwrap().pop_front().map(|(_, id)| id);
+    }
+
+    pub(crate) fn set_stale_after(&self, max_age: Option<Duration>) {
+        *self.stale_after.lock().unwrap() = max_age;
+    }
+
+    // Drops the events older than the stale limit, if one is set, and
+    // returns how many are left.
+    fn drop_stale(&self, now: Instant) -> usize {
+        let mut events = self.events.lock().unwrap();
+        if let Some(max_age) = *self.stale_after.lock().unwrap() {
+            events.retain(|(at, _)| now.duration_since(*at) <= max_age);
+        }
+        return events.len();
+    }
+}
+
+/// Marks a native modal dialog as open for as long as it lives. Queries from
+/// handles are refused meanwhile, and injected events wait in the queue until
+/// the dialog closes, when stale ones are dropped.
+pub(crate) struct ModalGuard {
+    events: Arc<EventQueue>
+}
+
+impl ModalGuard {
+    pub(crate) fn new(events: &Arc<EventQueue>) -> ModalGuard {
+        MODAL.store(true, Ordering::SeqCst);
+        return ModalGuard{ events: events.clone() };
+    }
+}
+
+impl Drop for ModalGuard {
+    fn drop(&mut self) {
+        MODAL.store(false, Ordering::SeqCst);
+        let waiting = self.events.drop_stale(Instant::now());
+        for _ in 0..waiting {
+            rstk::tell_wish(WAKE);
+        }
+    }
+}
+
+// Latest pending command per key, in the order keys were first updated.
+#[derive(Default)]
+struct Pending {
+    commands: HashMap<String, String>,
+    order: Vec<String>
+}
+
+impl Pending {
+    fn push(&mut self, key: &str, command: String) {
+        if self.commands.insert(key.to_string(), command).is_none() {
+            self.order.push(key.to_string());
+        }
+    }
+
+    fn take_all(&mut self) -> Vec<String> {
+        let mut commands = std::mem::take(&mut self.commands);
+        return self.order.drain(..).filter_map(|key| commands.remove(&key)).collect();
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        let command = self.commands.remove(key)?;
+        self.order.retain(|k| k != key);
+        return Some(command);
+    }
+}
+
+impl WindowHandle {
+    pub(crate) fn new(elements: HashMap<String, HandleElement>, events: Arc<EventQueue>) -> WindowHandle {
+        let shared = Arc::new(Shared{
+            elements,
+            pending: Mutex::new(Pending::default()),
+            interval: Mutex::new(Duration::from_secs_f64(1.0 / DEFAULT_MAX_RATE)),
+            events
+        });
+        let weak = Arc::downgrade(&shared);
+        thread::spawn(move || flush_loop(weak));
+        return WindowHandle{ shared };
+    }
+
+    fn element(&self, key: &str) -> Result<&HandleElement, RsgError> {
+        return self.shared.elements.get(key).ok_or(RsgError::UnknownKey(key.to_string()));
+    }
+
+    /// Thread-safe counterpart of [`Window::update_progress`](crate::Window::update_progress).
+    pub fn update_progress(&self, key: &str, value: impl Into<f64>) -> Result<(), RsgError> {
+        let element = self.element(key)?;
+        self.shared.pending.lock().unwrap().push(key, crate::progress_command(&element.id, value.into()));
+        return Ok(());
+    }
+
+    /// Thread-safe counterpart of [`Window::update_kv`](crate::Window::update_kv).
+    pub fn update_kv(&self, key: &str, value: &str) -> Result<(), RsgError> {
+        let entry = self.element(key)?.kv_entry.as_ref().ok_or(RsgError::WrongElementType(key.to_string()))?;
+        self.shared.pending.lock().unwrap().push(key, crate::readonly_entry_command(entry, value));
+        return Ok(());
+    }
+
+    /// Thread-safe counterpart of [`Window::set_gauge`](crate::Window::set_gauge).
+    pub fn set_gauge(&self, key: &str, value: impl Into<f64>) -> Result<(), RsgError> {
+        let element = self.element(key)?;
+        let gauge = element.gauge.ok_or(RsgError::WrongElementType(key.to_string()))?;
+        self.shared.pending.lock().unwrap().push(key, crate::gauge_command(&element.id, gauge, value.into()));
+        return Ok(());
+    }
+
+    /// Makes the element report a click, as if the user had clicked it. Any
+    /// pending update of the same element is sent first, so `read()` sees
+    /// the event only after the update.
+    ///
+    /// Events injected while a native dialog is open are held back until it
+    /// closes, see [`Window::set_stale_event_limit`](crate::Window::set_stale_event_limit).
+    pub fn inject_event(&self, key: &str) -> Result<(), RsgError> {
+        let id = &self.element(key)?.id;
+        let mut pending = self.shared.pending.lock().unwrap();
+        if let Some(command) = pending.take(key) {
+            rstk::tell_wish(&command);
+        }
+        self.shared.events.push(Instant::now(), id.clone());
+        if !modal() {
+            rstk::tell_wish(WAKE);
+        }
+        return Ok(());
+    }
+
+    /// Returns the current value of an input, slider, spin or kv row. The
+    /// main thread may be waiting in `read()` meanwhile.
+    ///
+    /// Fails with [`RsgError::Busy`] instead of waiting while a native
+    /// dialog is open.
+    pub fn value(&self, key: &str) -> Result<String, RsgError> {
+        let element = self.element(key)?;
+        let query = element.query.as_ref().ok_or(RsgError::WrongElementType(key.to_string()))?;
+        if modal() {
+            return Err(RsgError::Busy);
+        }
+        let value = rstk::ask_wish(&format!("puts [{}] ; flush stdout", query));
+        if element.number_input {
+            return Ok(crate::number_input_value(&value, crate::decimal_separator()));
+        }
+        return Ok(value);
+    }
+
+    /// Sets how many times a second pending updates are sent to wish.
+    pub fn set_max_rate(&self, hz: f64) {
+        *self.shared.interval.lock().unwrap() = Duration::from_secs_f64(1.0 / hz.max(0.1));
+    }
+
+    /// Sends all pending updates to wish now.
+    pub fn flush(&self) {
+        self.shared.flush();
+    }
+}
+
+impl Shared {
+    fn flush(&self) {
+        // Hold the lock while sending so a concurrent inject_event cannot
+        // overtake an update it should follow.
+        let mut pending = self.pending.lock().unwrap();
+        for command in pending.take_all() {
+            rstk::tell_wish(&command);
+        }
+    }
+}
+
+// Periodically sends pending updates until the window and every handle are
+// gone.
+fn flush_loop(shared: Weak<Shared>) {
+    loop {
+        let interval = match shared.upgrade() {
+            Some(shared) => *shared.interval.lock().unwrap(),
+            None => return
+        };
+        thread::sleep(interval);
+        match shared.upgrade() {
+            Some(shared) => shared.flush(),
+            None => return
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle_without_wish(events: &Arc<EventQueue>) -> WindowHandle {
+        let mut elements = HashMap::new();
+        elements.insert(String::from("input_0"), HandleElement{
+            id: String::from(".r1"), kv_entry: None, gauge: None, query: Some(String::from(".r1 get 0.0 end")), number_input: false
+        });
+        return WindowHandle::new(elements, events.clone());
+    }
+
+    #[test]
+    fn queries_are_refused_while_a_dialog_is_open() {
+        let events = Arc::new(EventQueue::default());
+        let handle = handle_without_wish(&events);
+
+        let dialog = ModalGuard::new(&events);
+        assert!(matches!(handle.value("input_0"), Err(RsgError::Busy)));
+        assert!(matches!(handle.value("missing"), Err(RsgError::UnknownKey(_))));
+
+        // nothing was waiting, so closing the dialog does not touch wish
+        drop(dialog);
+        assert!(!modal());
+    }
+
+    #[test]
+    fn stale_events_are_dropped_when_a_dialog_closes() {
+        let events = EventQueue::default();
+        let start = Instant::now();
+        events.push(start, String::from(".r1"));
+        events.push(start + Duration::from_millis(900), String::from(".r2"));
+        events.push(start + Duration::from_millis(950), String::from(".r3"));
+
+        events.set_stale_after(Some(Duration::from_millis(200)));
+        assert_eq!(2, events.drop_stale(start + Duration::from_millis(1000)));
+        assert_eq!(Some(String::from(".r2")), events.pop());
+        assert_eq!(Some(String::from(".r3")), events.pop());
+        assert_eq!(None, events.pop());
+    }
+
+    #[test]
+    fn events_are_kept_without_a_stale_limit() {
+        let events = EventQueue::default();
+        let start = Instant::now();
+        events.push(start, String::from(".r1"));
+        assert_eq!(1, events.drop_stale(start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn updates_to_one_key_coalesce() {
+        let mut pending = Pending::default();
+        pending.push("progress_0", String::from("1"));
+        pending.push("kv", String::from("a"));
+        pending.push("progress_0", String::from("2"));
+        pending.push("progress_0", String::from("3"));
+        assert_eq!(vec!["3", "a"], pending.take_all());
+        assert!(pending.take_all().is_empty());
+    }
+
+    #[test]
+    fn take_leaves_other_keys_pending() {
+        let mut pending = Pending::default();
+        pending.push("a", String::from("1"));
+        pending.push("b", String::from("2"));
+        assert_eq!(Some(String::from("1")), pending.take("a"));
+        assert_eq!(None, pending.take("a"));
+        pending.push("a", String::from("3"));
+        assert_eq!(vec!["2", "3"], pending.take_all());
+    }
+}
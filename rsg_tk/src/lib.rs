@@ -10,482 +10,4932 @@
 
 use rstk::{self, TkLabelOptions, TkGridLayout, TkWidget};
 
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+mod canvas;
+mod element;
+mod handle;
+mod registry;
+pub use canvas::RsgCanvas;
+pub use element::RsgElement;
+pub use handle::{WindowHandle, DEFAULT_MAX_RATE};
 
 pub use rsg_core::*;
 
+/// The event `read()` returns once the window has been closed. Events
+/// queued before the close are returned first; every read after it returns
+/// this again, with the values as they were last read.
+pub const WIN_CLOSED: &str = "Quit";
+
+/// Event `read()` returns when the user brings the window back from being
+/// minimized, e.g. to refresh what it shows.
+pub const RESTORED_KEY: &str = "__RESTORED__";
 
-pub fn text<T>(text_name: T) -> RsgObj where String: From<T> {
+/// Event returned by [`Window::read_timeout`] when no event came in time.
+pub const TIMEOUT_KEY: &str = "__TIMEOUT__";
+
+pub fn text(text_name: impl Into<String>) -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::Text,
-        name: String::from(text_name),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Text, text_name.into(), RsgObjEx::default())
     }
 }
-pub fn text_ex<T, U>(text_name: T, text_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+/// A text, `size` giving its width in characters and height in lines.
+pub fn text_ex<U>(text_name: impl Into<String>, text_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return RsgObj{
-        r#type: RsgObjType::Text,
-        name: String::from(text_name),
-        size: RsgObjEx::from(text_ex).size,
-        color: RsgObjEx::from(text_ex).color,
-        pad: RsgObjEx::from(text_ex).pad,
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Text, text_name.into(), RsgObjEx::from(text_ex))
     }
 }
 
 
-pub fn button<T>(button_name: T) -> RsgObj where String: From<T> {
+pub fn button(button_name: impl Into<String>) -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::Button,
-        name: String::from(button_name),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Button, button_name.into(), RsgObjEx::default())
     }
 }
-pub fn button_ex<T, U>(button_name: T, button_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn button_ex<U>(button_name: impl Into<String>, button_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return RsgObj{
-        r#type: RsgObjType::Button,
-        name: String::from(button_name),
-        size: RsgObjEx::from(button_ex).size,
-        color: RsgObjEx::from(button_ex).color,
-        pad: RsgObjEx::from(button_ex).pad,
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Button, button_name.into(), RsgObjEx::from(button_ex))
     }
 }
 
 
-pub fn checkbox<T>(checkbox_name: T) -> RsgObj where String: From<T> {
+pub fn checkbox(checkbox_name: impl Into<String>) -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::CheckBox,
-        name: String::from(checkbox_name),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::CheckBox, checkbox_name.into(), RsgObjEx::default())
     }
 }
-
-
-pub fn checkbox_ex<T, U>(checkbox_name: T, checkbox_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn checkbox_ex<U>(checkbox_name: impl Into<String>, checkbox_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return RsgObj{
-        r#type: RsgObjType::CheckBox,
-        name: String::from(checkbox_name),
-        size: RsgObjEx::from(checkbox_ex).size,
-        color: RsgObjEx::from(checkbox_ex).color,
-        pad: RsgObjEx::from(checkbox_ex).pad,
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::CheckBox, checkbox_name.into(), RsgObjEx::from(checkbox_ex))
     }
 }
 
 
-pub fn radio<T>(radio_name: T) -> RsgObj where String: From<T> {
+/// A radio button. The radios of one layout row form a group, of which one
+/// is selected at a time, unless `RsgObjEx::group` names another group.
+pub fn radio(radio_name: impl Into<String>) -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::Radio,
-        name: String::from(radio_name),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Radio, radio_name.into(), RsgObjEx::default())
     }
 }
-pub fn radio_ex<T, U>(radio_name: T, radio_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
-    return RsgObj{
-        r#type: RsgObjType::Radio,
-        name: String::from(radio_name),
-        size: RsgObjEx::from(radio_ex).size,
-        color: RsgObjEx::from(radio_ex).color,
-        pad: RsgObjEx::from(radio_ex).pad,
-        range: RsgObjEx::from(radio_ex).range
-    }
+pub fn radio_ex<U>(radio_name: impl Into<String>, radio_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Radio, radio_name.into(), RsgObjEx::from(radio_ex));
 }
 
 
 pub fn input() -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::Input,
-        name: String::from(""),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Input, String::from(""), RsgObjEx::default())
     }
 }
-pub fn input_ex<T, U>(input_placeholder: T, input_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn input_ex<U>(input_placeholder: impl Into<String>, input_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return RsgObj{
-        r#type: RsgObjType::Input,
-        name: String::from(input_placeholder),
-        size: RsgObjEx::from(input_ex).size,
-        color: RsgObjEx::from(input_ex).color,
-        pad: RsgObjEx::from(input_ex).pad,
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Input, input_placeholder.into(), RsgObjEx::from(input_ex))
     }
 }
 
-
-pub fn slider() -> RsgObj {
-    return RsgObj{
-        r#type: RsgObjType::Slider,
-        name: RsgOrientation::Horizontal.to_string(),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 100)
-    }
+/// An input whose value is checked with `check` each time the window reads
+/// values. While the check fails the input is marked, as by
+/// [`Window::validate_all`], and the values list "false" under
+/// "<key>.valid" right after the input's own value; "true" once it passes.
+pub fn input_validated(check: impl Fn(&str) -> bool + Send + Sync + 'static) -> RsgObj {
+    return input_validated_ex("", check, RsgObjEx::default());
 }
-pub fn slider_ex<T, U>(slider_orientation: T, slider_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn input_validated_ex<U>(input_placeholder: impl Into<String>, check: impl Fn(&str) -> bool + Send + Sync + 'static, input_validated_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return RsgObj{
-        r#type: RsgObjType::Slider,
-        name: RsgOrientation::from(slider_orientation).to_string(),
-        size: RsgObjEx::from(slider_ex).size,
-        color: RsgObjEx::from(slider_ex).color,
-        pad: RsgObjEx::from(slider_ex).pad,
-        range: RsgObjEx::from(slider_ex).range
+        check: Some(Arc::new(check)),
+        ..input_ex(input_placeholder, input_validated_ex)
     }
 }
 
-// TODO ? Split into HSeparator, VSeparator...
-pub fn separator() -> RsgObj {
+/// Suffix of the keys the validity of an [`input_validated`] is listed
+/// under in the values.
+pub const VALID_KEY_SUFFIX: &str = ".valid";
+
+/// An input only accepting numbers: digits with an optional sign and
+/// decimal point. Its value is the number typed, or empty while the field
+/// holds none. `number_input_ex` clamps the number to `range` when the field
+/// loses the focus.
+pub fn number_input() -> RsgObj {
     return RsgObj{
-        r#type: RsgObjType::Separator,
-        name: RsgOrientation::Horizontal.to_string(),
-        size: (0, 0),
-        color: (RsgColor::None, RsgColor::None),
-        pad: (10, 4),
-        range: (0, 0)
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::NumberInput, String::from(""), RsgObjEx::default())
     }
 }
-pub fn separator_ex<T, U>(separator_orientaiton: T, separator_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
-    return RsgObj{
-        r#type: RsgObjType::Separator,
-        name: RsgOrientation::from(separator_orientaiton).to_string(),
-        size: RsgObjEx::from(separator_ex).size,
-        color: RsgObjEx::from(separator_ex).color,
-        pad: RsgObjEx::from(separator_ex).pad,
-        range: RsgObjEx::from(separator_ex).range
+pub fn number_input_ex<U>(number_input_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::NumberInput, String::from(""), RsgObjEx::from(number_input_ex));
+}
+
+// Tcl creating a number input as an entry rejecting keystrokes that cannot
+// lead to a number, written with '.' or `separator` as decimal separator,
+// and clamping it to `range` on focus-out unless the range is (0, 0).
+fn number_input_commands(id: &str, width: u64, range: (i64, u64), separator: char) -> Vec<String> {
+    let decimal = match separator {
+        '.' => String::from("."),
+        c if c.is_alphanumeric() => format!(".{}", c),
+        c => format!(".\\{}", c)
+    };
+    let mut commands = vec![
+        format!("ttk::entry {} -width {} -validate key -validatecommand {{regexp {{^[-+]?[0-9]*[{}]?[0-9]*$}} %P}}", id, width, decimal)
+    ];
+    if range != (0, 0) {
+        commands.push(format!("bind {} <FocusOut> {{rsg_number_clamp %W {} {}}}", id, range.0, range.1));
     }
+    return commands;
 }
 
+// Tcl returning the text of a number input, see `number_input_value`.
+fn number_input_query(id: &str) -> String {
+    return format!("{} get", id);
+}
 
+// The number in a number input as `read()` reports it, written with '.'
+// whichever separator it was typed with, and empty if there is none.
+fn number_input_value(text: &str, separator: char) -> String {
+    return parse_decimal_with(text, separator).map(|n| n.to_string()).unwrap_or_default();
+}
 
-pub struct Window{
-    widget_ids_to_names: HashMap<String, String>,
-    inputs: Vec<String>,
-    name: String,
-    layout: Vec<Vec<RsgObj>>,
-    root: rstk::TkTopLevel,
-    sliders: Vec<String>
+
+// Tcl creating a slider. It is a classic Tk scale rather than a ttk one, for
+// its resolution, ticks and readout: values are rounded to `step`, so whole
+// steps read back as integers. The size along the slider becomes its
+// `-length` and the size across it its `-width`; zero leaves Tk's size.
+fn slider_commands(id: &str, x: &RsgObj) -> Vec<String> {
+    let vertical = matches!(RsgOrientation::to_enum(&x.name), RsgOrientation::Vertical);
+    let mut command = format!("scale {} -orient {} -from {} -to {} -resolution {}",
+                              id, x.name, x.range.0, x.range.1, x.step);
+    if x.tick_interval > 0.0 {
+        command.push_str(&format!(" -tickinterval {}", x.tick_interval));
+    }
+    if !x.show_value {
+        command.push_str(" -showvalue 0");
+    }
+    let (length, width) = if vertical { (x.size.1, x.size.0) } else { (x.size.0, x.size.1) };
+    if length != 0 {
+        command.push_str(&format!(" -length {}", length));
+    }
+    if width != 0 {
+        command.push_str(&format!(" -width {}", width));
+    }
+    return vec![command];
 }
 
 
-pub fn window<T, U>(window_name: T, layout: U) -> Window where String: From<T>, Vec<Vec<RsgObj>>: From<U> {
-    let mut new = Window{
-        widget_ids_to_names: HashMap::new(),
-        inputs: Vec::new(),
-        name: String::from(window_name),
-        layout: layout.into(),
-        root: rstk::start_wish().unwrap(),
-        sliders: vec![]
-    };
+// Tcl making an input report its text after every key, as one line: line
+// breaks are reported as spaces, the values keep the exact text.
+fn input_events_command(id: &str) -> String {
+    return format!("bind {} <KeyRelease> {{+puts \"cb1s-%W-[string map {{\\n {{ }}}} [%W get 1.0 end-1c]]\" ; flush stdout}}", id);
+}
 
-    for i in 0..new.layout.len() {
-        for j in 0..new.layout[i].len() {
-            let x = &new.layout[i][j];
+// Tcl aligning an element to one side of its grid cell and lining up its
+// text. Text widgets only justify through a tag, which is put back on the
+// whole text after every key so typed text is justified too.
+fn justify_commands(id: &str, r#type: &RsgObjType, justify: RsgJustify) -> Vec<String> {
+    let (side, anchor, word) = match justify {
+        RsgJustify::Left => ("w", "w", "left"),
+        RsgJustify::Center => return vec![],
+        RsgJustify::Right => ("e", "e", "right")
+    };
+    let mut commands = vec![format!("grid configure {} -sticky {}", id, side)];
+    match r#type {
+        RsgObjType::Text => commands.push(format!("{} configure -anchor {} -justify {}", id, anchor, word)),
+        RsgObjType::Input => {
+            commands.push(format!("{} tag configure rsg_justify -justify {}", id, word));
+            commands.push(format!("{} tag add rsg_justify 1.0 end", id));
+            commands.push(format!("bind {} <KeyRelease> {{+%W tag add rsg_justify 1.0 end}}", id));
+        }
+        RsgObjType::NumberInput => commands.push(format!("{} configure -justify {}", id, word)),
+        _ => {}
+    }
+    return commands;
+}
 
-            match x.r#type {
-                RsgObjType::Text => {
-                    let n = rstk::make_label(&new.root);
-                    n.text(&x.name);
+// Tcl stretching an element over its cell at (row, column) of `parent`'s
+// grid, and weighting that column and row so they grow with the window.
+fn expand_commands(parent: &str, id: &str, cell: (usize, usize), expand: (bool, bool)) -> Vec<String> {
+    let mut commands: Vec<String> = vec![];
+    let sticky = match expand {
+        (false, false) => return commands,
+        (true, false) => "ew",
+        (false, true) => "ns",
+        (true, true) => "nsew"
+    };
+    commands.push(format!("grid configure {} -sticky {}", id, sticky));
+    if expand.0 { commands.push(format!("grid columnconfigure {} {} -weight 1", parent, cell.1)); }
+    if expand.1 { commands.push(format!("grid rowconfigure {} {} -weight 1", parent, cell.0)); }
+    return commands;
+}
 
-                    if x.size.0 != 0 && x.size.1 != 0 {
-                        n.font(&rstk::TkFont{
-                            size: ((x.size.0 + x.size.1) / 2) as u64,
-                            ..Default::default()
-                        });
-                    }
+// Tcl wrapping a text element's lines at `wrap` pixels, or at whatever width
+// its cell gives it once it stretches with the window.
+fn wrap_commands(id: &str, wrap: u64, stretches: bool) -> Vec<String> {
+    let mut commands = vec![format!("{} configure -wraplength {}", id, wrap)];
+    if stretches {
+        commands.push(format!("bind {} <Configure> {{+%W configure -wraplength %w}}", id));
+    }
+    return commands;
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0 as u64).pady(x.pad.1 as u64)
-                    .layout();
+// Tcl returning a checkbox's value: "true", "false", or "mixed" for a
+// tristate checkbox in its third state.
+fn checkbox_query(id: &str) -> String {
+    return format!("expr {{[set [{} cget -variable]] eq \"mixed\" ? \"mixed\" : [set [{} cget -variable]] ? \"true\" : \"false\"}}", id, id);
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+// Name of the Tk variable shared by the radios of a group: the explicit
+// `group`, or else the layout row of `parent` the radio is in. Characters
+// that cannot appear in a plain Tcl variable name are spelled in hex.
+fn radio_group_name(parent: &str, row: usize, group: Option<&str>) -> String {
+    match group {
+        Some(group) => {
+            let mut name = String::from("key_");
+            for c in group.chars() {
+                if c.is_ascii_alphanumeric() { name.push(c); } else { name.push_str(&format!("_{:x}", c as u32)); }
+            }
+            return name;
+        }
+        None => return format!("{}_row{}", parent.replace('.', "_"), row)
+    }
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
-                }
-                RsgObjType::Button => {
-                    let n = rstk::make_button(&new.root);
-                    n.text(&x.name);
+// Directions an element stretches in: those of its own `expand`, and for
+// containers those of the elements inside, so that their growth is not
+// capped by a container keeping its size.
+fn grows(x: &RsgObj) -> (bool, bool) {
+    let mut stretch = x.expand;
+    for inner in x.layout.iter().flatten() {
+        let inner = grows(inner);
+        stretch = (stretch.0 || inner.0, stretch.1 || inner.1);
+    }
+    return stretch;
+}
 
-                    if x.size.0 != 0 { n.width(x.size.0 as i64); }
-                    if x.size.1 != 0 { n.height(x.size.1 as i64); }
+// Tcl giving a checkbox, radio or slider the value it starts with. Radios
+// not starting selected leave their group alone; "mixed" only shows on
+// tristate checkboxes.
+fn default_command(id: &str, r#type: &RsgObjType, value: &str) -> Option<String> {
+    if value.is_empty() {
+        return None;
+    }
+    let on = value == "true" || value == "1";
+    match r#type {
+        RsgObjType::CheckBox if value == "mixed" => return Some(format!("set [{} cget -variable] mixed", id)),
+        RsgObjType::CheckBox => return Some(format!("set [{} cget -variable] {}", id, if on { 1 } else { 0 })),
+        RsgObjType::Radio if on => return Some(format!("set [{} cget -variable] [{} cget -value]", id, id)),
+        RsgObjType::Slider => return Some(format!("{} set {}", id, tcl_string(value))),
+        _ => return None
+    }
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0 as u64).pady(x.pad.1 as u64)
-                    .layout();
+/// A horizontal slider over `range`, `size.0` setting its length and
+/// `size.1` its thickness in pixels. Its value is rounded to `step`, and
+/// `read()` gives it as Tcl prints it: "42" for whole steps, "4.5" for a
+/// step of 0.5. `default` sets the value it starts at, `tick_interval`
+/// labels ticks along it and `show_value` shows the value next to it.
+pub fn slider() -> RsgObj {
+    return RsgObj::new(RsgObjType::Slider, RsgOrientation::Horizontal.to_string(), RsgObjEx::default());
+}
+pub fn slider_ex<T, U>(slider_orientation: T, slider_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Slider, RsgOrientation::from(slider_orientation).to_string(), RsgObjEx::from(slider_ex));
+}
 
+/// A vertical slider, from 0 at the top to 100 at the bottom unless `range`
+/// says otherwise. `size.1` sets its length and `size.0` its thickness.
+pub fn vslider() -> RsgObj {
+    return vslider_ex(RsgObjEx::default());
+}
+pub fn vslider_ex<U>(vslider_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return slider_ex(RsgOrientation::Vertical, vslider_ex);
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+/// A horizontal line, stretched across its column. `size.0` sets a fixed
+/// length and `size.1` the thickness, both in pixels, and the first color
+/// set in `color` colors the line.
+pub fn hseparator() -> RsgObj {
+    return hseparator_ex(RsgObjEx::default());
+}
+pub fn hseparator_ex<U>(hseparator_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return separator_ex(RsgOrientation::Horizontal, hseparator_ex);
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
+/// A vertical line, stretched to the height of its row. `size.0` sets a
+/// fixed length and `size.1` the thickness, both in pixels, and the first
+/// color set in `color` colors the line.
+pub fn vseparator() -> RsgObj {
+    return vseparator_ex(RsgObjEx::default());
+}
+pub fn vseparator_ex<U>(vseparator_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return separator_ex(RsgOrientation::Vertical, vseparator_ex);
+}
 
-                    n.command(||{});
-                    new.widget_ids_to_names
-                    .entry(n.id().to_string()).or_insert(x.name.clone());
-                }
-                RsgObjType::CheckBox => {
-                    let n = rstk::make_check_button(&new.root);
-                    n.text(&x.name);
+/// Same as [`hseparator`].
+pub fn separator() -> RsgObj {
+    return hseparator();
+}
+/// Same as [`hseparator_ex`] or [`vseparator_ex`], depending on the orientation.
+pub fn separator_ex<T, U>(separator_orientaiton: T, separator_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        range: (0, 0),
+        ..RsgObj::new(RsgObjType::Separator, RsgOrientation::from(separator_orientaiton).to_string(), RsgObjEx::from(separator_ex))
+    }
+}
 
-                    if x.size.0 != 0 { n.width(x.size.0 as i64); }
-                    if x.size.1 != 0 { n.height(x.size.1 as i64); }
+// Tcl creating a ttk separator with a set size or color, `size.0` being the
+// length and `size.1` the thickness. The separator element always draws a
+// thin line in the theme's colors, so the separator gets a style of its own
+// whose layout fills the line color over a padding element sized by the
+// style's -padding. A line without a set length stretches along its cell.
+fn styled_separator_commands(id: &str, vertical: bool, size: (u64, u64), color: (RsgColor, RsgColor)) -> Vec<String> {
+    let (length, thickness) = size;
+    let thickness = if thickness == 0 { 2 } else { thickness };
+    let line = match color {
+        (RsgColor::None, RsgColor::None) => String::from("gray50"),
+        (RsgColor::None, bg) => get_rsg_color(bg),
+        (fg, _) => get_rsg_color(fg)
+    };
+    let (width, height) = if vertical { (thickness, length) } else { (length, thickness) };
+    let style = format!("Rsg{}.TSeparator", id.replace('.', "_"));
+    return vec![
+        format!("ttk::style configure {} -background {{{}}} -padding {{0 0 {} {}}}", style, line, width, height),
+        format!("ttk::style layout {} {{Separator.fill -sticky nsew -children {{Separator.padding -sticky nsew}}}}", style),
+        format!("ttk::separator {} -orient {} -style {}", id, if vertical { "vertical" } else { "horizontal" }, style)
+    ];
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0 as u64).pady(x.pad.1 as u64)
-                    .layout();
+pub fn spin() -> RsgObj {
+    return RsgObj::new(RsgObjType::Spin, String::from(""), RsgObjEx::default());
+}
+pub fn spin_ex<U>(spin_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Spin, String::from(""), RsgObjEx::from(spin_ex));
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+pub fn progress_bar() -> RsgObj {
+    return RsgObj::new(RsgObjType::Progress, RsgOrientation::Horizontal.to_string(), RsgObjEx::default());
+}
+pub fn progress_bar_ex<T, U>(progress_bar_orientation: T, progress_bar_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Progress, RsgOrientation::from(progress_bar_orientation).to_string(), RsgObjEx::from(progress_bar_ex));
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
+pub fn image(image_path: impl Into<String>) -> RsgObj {
+    return RsgObj::new(RsgObjType::Image, image_path.into(), RsgObjEx::default());
+}
+pub fn image_ex<U>(image_path: impl Into<String>, image_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Image, image_path.into(), RsgObjEx::from(image_ex));
+}
 
-                    n.command(|_|{});
-                    new.widget_ids_to_names
-                    .entry(n.id().to_string()).or_insert(x.name.clone());
-                }
-                RsgObjType::Radio => {
-                    let mut group: (u64, u64) = (0, 0);
 
-                    if x.pad.0 != 0 { group.0 = x.pad.0 }
-                    if x.pad.1 != 0 { group.0 = x.pad.0 }
-                    if x.pad.0 == 0 && x.pad.1 == 0 { group = (0, i as u64) }
+/// An image from encoded data in memory, e.g. from `include_bytes!`, in any
+/// format Tk reads (PNG, GIF or PPM). Clicks are reported under its key.
+pub fn image_from_bytes(data: &[u8]) -> RsgObj {
+    return image_from_bytes_ex(data, RsgObjEx::default());
+}
+pub fn image_from_bytes_ex<U>(data: &[u8], image_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        data: data.to_vec(),
+        ..RsgObj::new(RsgObjType::Image, String::from(""), RsgObjEx::from(image_ex))
+    }
+}
 
-                    let n = rstk::make_radio_button(&new.root, &format!("{}x{}", group.0, group.1), &x.name);
-                    n.text(&x.name);
+// Where an image comes from: a file, or encoded data in memory.
+#[derive(Clone)]
+#[derive(Copy)]
+enum ImageSource<'a> {
+    Path(&'a str),
+    Bytes(&'a [u8])
+}
 
-                    if x.size.0 != 0 { n.width(x.size.0 as i64); };
-                    if x.size.1 != 0 { n.width(x.size.0 as i64); };
+impl<'a> ImageSource<'a> {
+    fn of(x: &'a RsgObj) -> ImageSource<'a> {
+        if x.data.is_empty() {
+            return ImageSource::Path(&x.name);
+        }
+        return ImageSource::Bytes(&x.data);
+    }
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0 as u64).pady(x.pad.1 as u64)
-                    .layout();
+// Pipes to wish are written a line at a time, so image data is sent in
+// pieces of this many base64 characters.
+const IMAGE_CHUNK: usize = 64 * 1024;
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for k in 0..4 {
+            if k <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * k)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    return encoded;
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
+// Tcl collecting image data into `var`, one piece per command.
+fn image_data_commands(var: &str, data: &[u8]) -> Vec<String> {
+    let encoded = base64(data);
+    let mut commands = vec![format!("set {} {{}}", var)];
+    for piece in encoded.as_bytes().chunks(IMAGE_CHUNK) {
+        // base64 is ASCII, so any split is on a char boundary
+        commands.push(format!("append {} {}", var, std::str::from_utf8(piece).unwrap()));
+    }
+    return commands;
+}
 
-                    n.command(|_|{});
-                    new.widget_ids_to_names
-                    .entry(n.id().to_string()).or_insert(x.name.clone());
-                }
-                RsgObjType::Input => {
-                    let n = rstk::make_text(&new.root);
-                    n.insert((0, 0), &x.name);
+// Creates a Tk photo from the source, scaled down and clipped to `size` when
+// one is given. Tk's own error is caught so a bad file cannot leave a Tcl
+// error in the middle of the event stream.
+fn load_image(source: ImageSource, size: (u64, u64)) -> Result<String, String> {
+    let img = rstk::next_wid(".");
+    let create = match source {
+        ImageSource::Path(path) => {
+            if let Err(err) = std::fs::File::open(path) {
+                return Err(err.to_string());
+            }
+            format!("image create photo {} -file {{{}}}", img, path)
+        }
+        ImageSource::Bytes(data) => {
+            for command in image_data_commands("::rsg_image_data", data) {
+                rstk::tell_wish(&command);
+            }
+            format!("image create photo {} -data $::rsg_image_data", img)
+        }
+    };
+    let result = rstk::ask_wish(&format!(
+        "if {{[catch {{{}}} err]}} {{puts \"err $err\"}} else {{puts ok}} ; unset -nocomplain ::rsg_image_data ; flush stdout",
+        create
+    ));
+    if result != "ok" {
+        return Err(result.trim_start_matches("err ").to_string());
+    }
 
-                    let new_name = x.name.clone();
-                    if new_name != "".to_string() { n.insert((0, 0), &new_name); }
+    if size.0 == 0 || size.1 == 0 {
+        return Ok(img);
+    }
 
-                    if x.size.0 == 0 { n.width(10); }
-                    else { n.width(x.size.0 as u64); }
-                    if x.size.1 == 0 { n.height(1); }
-                    else { n.height(x.size.1 as u64); }
+    let width: u64 = rstk::ask_wish(&format!("puts [image width {}] ; flush stdout", img)).parse().unwrap_or(0);
+    let height: u64 = rstk::ask_wish(&format!("puts [image height {}] ; flush stdout", img)).parse().unwrap_or(0);
+    let factor = std::cmp::max(1, std::cmp::max(width.div_ceil(size.0), height.div_ceil(size.1)));
 
-                    new.inputs.push(n.id().to_string());
+    let fitted = rstk::next_wid(".");
+    rstk::tell_wish(&format!("image create photo {} -width {} -height {}", fitted, size.0, size.1));
+    rstk::tell_wish(&format!("{} copy {} -subsample {} {}", fitted, img, factor, factor));
+    rstk::tell_wish(&format!("image delete {}", img));
+    return Ok(fitted);
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0 as u64).pady(x.pad.1 as u64)
-                    .layout();
+pub fn frame(frame_title: impl Into<String>, frame_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> RsgObj {
+    return RsgObj{
+        layout: collect_layout(frame_layout),
+        ..RsgObj::new(RsgObjType::Frame, frame_title.into(), RsgObjEx::default())
+    }
+}
+pub fn frame_ex<V>(frame_title: impl Into<String>, frame_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, frame_ex: V) -> RsgObj where RsgObjEx: From<V>, V: Copy {
+    return RsgObj{
+        layout: collect_layout(frame_layout),
+        ..RsgObj::new(RsgObjType::Frame, frame_title.into(), RsgObjEx::from(frame_ex))
+    }
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+pub fn column(column_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> RsgObj {
+    return RsgObj{
+        layout: collect_layout(column_layout),
+        ..RsgObj::new(RsgObjType::Column, String::from(""), RsgObjEx::default())
+    }
+}
+pub fn column_ex<V>(column_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, column_ex: V) -> RsgObj where RsgObjEx: From<V>, V: Copy {
+    return RsgObj{
+        layout: collect_layout(column_layout),
+        ..RsgObj::new(RsgObjType::Column, String::from(""), RsgObjEx::from(column_ex))
+    }
+}
+/// Two layouts side by side, or one above the other, split by a sash the
+/// user can drag. Each side is a [`column`] and is keyed as one. `size.0`
+/// sets the initial sash position in pixels.
+pub fn paned<T>(first_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, second_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, paned_orientation: T) -> RsgObj where RsgOrientation: From<T> {
+    return paned_ex(first_layout, second_layout, paned_orientation, RsgObjEx::default());
+}
+pub fn paned_ex<T, V>(first_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, second_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, paned_orientation: T, paned_ex: V) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<V>, V: Copy {
+    return RsgObj{
+        layout: vec![vec![column(first_layout), column(second_layout)]],
+        ..RsgObj::new(RsgObjType::Paned, RsgOrientation::from(paned_orientation).to_string(), RsgObjEx::from(paned_ex))
+    }
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
-                }
-                RsgObjType::Slider => {
-                    let rsg_orientation = RsgOrientation::to_enum(&x.name);
-                    let mut rstk_orientation: rstk::Orientation;
-                    if let RsgOrientation::Horizontal = rsg_orientation {
-                        rstk_orientation = rstk::Orientation::Horizontal;
-                    } else { rstk_orientation = rstk::Orientation::Vertical; }
-                    
-                    let n = rstk::make_scale(&new.root, rstk_orientation);
+// Tcl handing the sides of a paned element, built as its children, to the
+// panedwindow, and placing the sash once the window has a size.
+fn paned_commands(id: &str, sash: u64) -> Vec<String> {
+    let mut commands = vec![format!("foreach c [winfo children {}] {{ {} add $c -weight 1 }}", id, id)];
+    if sash != 0 {
+        commands.push(format!("bind {} <Map> {{ bind {} <Map> {{}} ; after idle {{ {} sashpos 0 {} }} }}", id, id, id, sash));
+    }
+    return commands;
+}
 
+/// A surface to draw on through [`Window::canvas`]. `size` is in pixels.
+/// With `enable_events`, clicks are reported with the coordinates as value,
+/// e.g. "canvas_0:::12,34".
+pub fn canvas() -> RsgObj {
+    return RsgObj::new(RsgObjType::Canvas, String::from(""), RsgObjEx::default());
+}
+pub fn canvas_ex<U>(canvas_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Canvas, String::from(""), RsgObjEx::from(canvas_ex));
+}
 
-                    rstk::tell_wish(&format!("{} configure -from {} -to {}", n.id(), x.range.0, x.range.1));
+/// An XY plot, with the x axis spanning `range` and the y axis `y_range`.
+/// Points are added with [`Window::graph_plot`]; `size` is in pixels.
+pub fn graph() -> RsgObj {
+    return RsgObj::new(RsgObjType::Graph, String::from(""), RsgObjEx::default());
+}
+pub fn graph_ex<U>(graph_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Graph, String::from(""), RsgObjEx::from(graph_ex));
+}
 
-                    //if x.size.0 != 0 { n.width(x.size.0); }
-                    //if x.size.1 != 0 { n.height(x.size.1); }
+/// A semicircular dial with a needle showing a value between `min` and
+/// `max`, moved with [`Window::set_gauge`]. `size.0` is the width in pixels;
+/// the first color draws the dial and the second fills the background.
+pub fn gauge(min: i64, max: u64) -> RsgObj {
+    return gauge_ex(min, max, RsgObjEx::default());
+}
+pub fn gauge_ex<U>(min: i64, max: u64, gauge_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        range: (min, max),
+        ..RsgObj::new(RsgObjType::Gauge, String::from(""), RsgObjEx::from(gauge_ex))
+    }
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0).pady(x.pad.1)
-                    .layout();
+// The range and width of a gauge, which place its needle.
+#[derive(Clone)]
+#[derive(Copy)]
+pub(crate) struct Gauge {
+    range: (i64, u64),
+    width: u64
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+// Draws gauge `c` as a Plotchart XY plot showing `value`: the dial is a
+// semicircle spanning the x axis from `min` to `max`, so the axis labels its
+// ends, and the value is shown as the title. Plotchart cannot move a plotted
+// series, so the plot is erased and drawn again for each value.
+const GAUGE_PROCS: &str = "proc rsg_gauge {c min max value} {
+    global rsg_gauge_plot rsg_gauge_colour
+    if {[info exists rsg_gauge_plot($c)]} { ::Plotchart::eraseplot $rsg_gauge_plot($c) }
+    set span [expr {$max > $min ? $max - $min : 1}]
+    set r [expr {$span / 2.0}]
+    set cx [expr {$min + $r}]
+    set p [::Plotchart::createXYPlot $c [list $min [expr {$min + $span}] $span] [list 0 $r $r]]
+    set rsg_gauge_plot($c) $p
+    $p yconfig -format { }
+    $p dataconfig dial -colour $rsg_gauge_colour($c) -width 6
+    $p dataconfig needle -colour $rsg_gauge_colour($c) -width 3
+    for {set i 0} {$i <= 36} {incr i} {
+        set a [expr {acos(-1) * $i / 36.0}]
+        $p plot dial [expr {$cx + $r * cos($a)}] [expr {$r * sin($a)}]
+    }
+    set a [expr {acos(-1) * ($min + $span - $value) / double($span)}]
+    $p plot needle $cx 0
+    $p plot needle [expr {$cx + 0.85 * $r * cos($a)}] [expr {0.85 * $r * sin($a)}]
+    $p title $value
+}";
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
+// Tcl sizing the canvas `id` of a gauge and setting the color its dial and
+// needle are drawn in by `gauge_command`.
+fn gauge_draw_commands(id: &str, gauge: Gauge, color: &str) -> Vec<String> {
+    return vec![
+        format!("{} configure -width {} -height {} -highlightthickness 0", id, gauge.width, gauge.width / 2 + 60),
+        format!("set ::rsg_gauge_colour({}) {{{}}}", id, color)
+    ];
+}
 
-                    new.sliders.push(n.id().to_string());
-                }
-                RsgObjType::Separator => {
-                    let rsg_orientation = RsgOrientation::to_enum(&x.name);
-                    let rstk_orientation: rstk::Orientation;
-                    if let RsgOrientation::Horizontal = rsg_orientation {
-                        rstk_orientation = rstk::Orientation::Horizontal;
-                    } else { rstk_orientation = rstk::Orientation::Vertical; }
+// Tcl pointing a gauge's needle at `value`, clamped to its range.
+pub(crate) fn gauge_command(id: &str, gauge: Gauge, value: f64) -> String {
+    let (min, max) = (gauge.range.0 as f64, gauge.range.1 as f64);
+    let value = value.max(min).min(max);
+    return format!("rsg_gauge {} {} {} {}", id, gauge.range.0, gauge.range.1, value);
+}
 
-                    let n = rstk::make_separator(&new.root, rstk_orientation);
+/// A read-only text area collecting lines printed with [`Window::print`] or
+/// the [`rsg_print!`] macro, scrolled to the newest line. `size` is in
+/// characters and `max_lines` caps how many lines are kept.
+pub fn output() -> RsgObj {
+    return RsgObj::new(RsgObjType::Output, String::from(""), RsgObjEx::default());
+}
+pub fn output_ex<U>(output_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::Output, String::from(""), RsgObjEx::from(output_ex));
+}
 
-                    n.grid()
-                    .row(i as u64).column(j as u64)
-                    .padx(x.pad.0).pady(x.pad.1)
-                    .layout();
+/// A read-only text area with a vertical scrollbar, for logs streamed in
+/// with [`Window::append_text`]. `size` is in characters. Unlike an
+/// [`output`], it only follows new lines while scrolled to the end.
+pub fn scrolled_text() -> RsgObj {
+    return RsgObj::new(RsgObjType::ScrolledText, String::from(""), RsgObjEx::default());
+}
+pub fn scrolled_text_ex<U>(scrolled_text_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::ScrolledText, String::from(""), RsgObjEx::from(scrolled_text_ex));
+}
 
-                    if let RsgColor::None = x.color.0 {
-                        if let RsgColor::None = x.color.1 {
-                        } else { rstk::tell_wish(&format!("{} configure -activebackground {}",n.id(), n.cget("bg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -fg {}", n.id(), get_rsg_color(x.color.0)));
-                        rstk::tell_wish(&format!("{} configure -activebackground {}", n.id(), get_rsg_color(x.color.0)))
-                    }
+// Appends a line to a scrolled text, which is only editable while it is
+// inserted, and scrolls to it unless the user has scrolled up.
+fn append_text_command(id: &str, line: &str) -> String {
+    return format!(
+        "set ::rsg_at_end [expr {{[lindex [{} yview] 1] == 1.0}}] ; {} configure -state normal ; {} insert end {} ; \
+         {} configure -state disabled ; if {{$::rsg_at_end}} {{ {} see end }}",
+        id, id, id, tcl_string(&format!("{}\n", line)), id, id
+    );
+}
 
-                    if let RsgColor::None = x.color.1 {
-                        if let RsgColor::None = x.color.0 {
-                        } else { rstk::tell_wish(&format!("{} configure -activeforeground {}", n.id(), n.cget("fg"))) }
-                    } else {
-                        rstk::tell_wish(&format!("{} configure -bg {}",n.id(), get_rsg_color(x.color.1)));
-                        rstk::tell_wish(&format!("{} configure -activeforeground {}",n.id(), get_rsg_color(x.color.1)))
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    return new;
+/// Prints to the output element created last, or to stdout when there is
+/// none or the window was closed, like the standard `print!`: no newline
+/// is added.
+#[macro_export]
+macro_rules! rsg_print {
+    ($($arg:tt)*) => { $crate::print_to_output(&format!($($arg)*)) };
 }
 
-impl Window {
-    pub fn read(&self) -> (String, Vec<String>) {
-        let event = rstk::mainloop().unwrap_or(String::from(""));
+/// Like [`rsg_print!`], ending the line as the standard `println!` does.
+#[macro_export]
+macro_rules! rsg_println {
+    () => { $crate::print_to_output("\n") };
+    ($($arg:tt)*) => { $crate::print_to_output(&format!("{}\n", format!($($arg)*))) };
+}
 
-        if Some(event.clone()).is_some() {
+// The output element created last and its line cap, for `rsg_print!`.
+static LAST_OUTPUT: Mutex<Option<(String, u64)>> = Mutex::new(None);
 
-            let or = String::from("None");
+#[doc(hidden)]
+pub fn print_to_output(text: &str) {
+    let mut output = LAST_OUTPUT.lock().unwrap();
+    if rstk::wish_gone() {
+        *output = None;
+    }
+    match &*output {
+        Some((id, max_lines)) => rstk::tell_wish(&output_command(id, text, *max_lines)),
+        None => std::print!("{}", text)
+    }
+}
 
-            //println!("{}", event);
+// Appends text to an output element, drops the oldest lines beyond
+// `max_lines` and scrolls to the end.
+fn output_command(id: &str, text: &str, max_lines: u64) -> String {
+    let mut command = format!("{} configure -state normal ; {} insert end {}", id, id, tcl_string(text));
+    if max_lines != 0 {
+        // the text always ends with an empty line after the last newline
+        command.push_str(&format!(
+            " ; set n [expr {{[lindex [split [{} index end] .] 0] - 2}}] ; if {{$n > {}}} {{ {} delete 1.0 [expr {{$n - {} + 1}}].0 }}",
+            id, max_lines, id, max_lines
+        ));
+    }
+    command.push_str(&format!(" ; {} see end ; {} configure -state disabled", id, id));
+    return command;
+}
 
-            let ev: String; 
+/// The resize handle in the bottom-right corner of the window, wherever it
+/// is put in the layout. It takes no grid cell of its own and is drawn over
+/// whatever reaches the corner, such as a [`status_bar`].
+pub fn sizegrip() -> RsgObj {
+    return RsgObj::new(RsgObjType::SizeGrip, String::from(""), RsgObjEx::default());
+}
 
-            if event.contains("-cbsep-") {
-                let parts: Vec<&str> = event.split("-cbsep-").collect();
-                let widget = self.widget_ids_to_names.get(parts[0].trim()).unwrap_or(&or);
-                let value = parts[1].trim();
-                ev = widget.to_owned() + ":::" + value;
-            } else {
-                ev = self.widget_ids_to_names.get(&event).unwrap_or(&or).clone();
-            }
+/// A title row drawn by the window itself, with minimize and close buttons,
+/// stretched across every column of the layout and meant for the first row
+/// of a window without a title bar of its own. Dragging the title moves the
+/// window. Closing goes through the same path as the window manager's close
+/// button, so `read()` reports [`WIN_CLOSED`].
+pub fn titlebar(title: impl Into<String>) -> RsgObj {
+    return RsgObj::new(RsgObjType::TitleBar, title.into(), RsgObjEx::default());
+}
+pub fn titlebar_ex<U>(title: impl Into<String>, titlebar_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::TitleBar, title.into(), RsgObjEx::from(titlebar_ex));
+}
 
-            let mut ret_values: Vec<String> = Vec::new();
+// The buttons of a titlebar and the drag bindings on its frame and title.
+// Minimizing is caught as window managers refuse to iconify windows they do
+// not decorate.
+fn titlebar_commands(frame: &str, title: &str, minimize: &str, close: &str) -> Vec<String> {
+    let mut commands = vec![
+        format!("ttk::button {} -text _ -width 3 -command {{catch {{wm iconify .}}}}", minimize),
+        format!("ttk::button {} -text \"\\u2715\" -width 3 -command {{eval [wm protocol . WM_DELETE_WINDOW]}}", close),
+        format!("grid {} -row 0 -column 0 -sticky w", title),
+        format!("grid {} -row 0 -column 1", minimize),
+        format!("grid {} -row 0 -column 2", close),
+        format!("grid columnconfigure {} 0 -weight 1", frame)
+    ];
+    for w in [frame, title] {
+        commands.push(format!("bind {} <ButtonPress-1> {{rsg_drag_start %X %Y}}", w));
+        commands.push(format!("bind {} <B1-Motion> {{rsg_drag_move %X %Y}}", w));
+    }
+    return commands;
+}
 
-            for each in &self.inputs {
-                let x = rstk::ask_wish(&format!(
-                    "puts [{} get {}.{} end] ; flush stdout",
-                    each, 0, 0
-                ));
-                ret_values.push(x);            
-            }
-            for each in &self.sliders {
-                let x = rstk::ask_wish(&format!(
-                        "puts [{} get] ; flush stdout",
-                        each
-                ));
-                ret_values.push(x.to_string());
-            }
-            return (ev.to_string(), ret_values);
-        } else {
-            return ("".to_string(), vec!["".to_string()])
-        }
+/// A menu bar entry reporting its path, e.g. "File/Save", from `read()`
+/// when chosen. See [`Window::set_menu_bar`].
+pub fn menu_item(label: impl Into<String>) -> RsgMenuItem {
+    return RsgMenuItem::new(RsgMenuKind::Command, label.into());
+}
+/// A menu entry with a check mark, toggled when chosen. It reports e.g.
+/// "Options/Wrap:::true" and its value is "true" or "false".
+pub fn menu_check(label: impl Into<String>, checked: bool) -> RsgMenuItem {
+    return RsgMenuItem::new(RsgMenuKind::Check(checked), label.into());
+}
+/// A menu entry of `group`, of which one entry is chosen at a time. The
+/// value keyed by the group name is the label of the chosen entry, empty
+/// until one is chosen.
+pub fn menu_radio(label: impl Into<String>, group: impl Into<String>) -> RsgMenuItem {
+    return RsgMenuItem::new(RsgMenuKind::Radio(group.into()), label.into());
+}
+pub fn menu_separator() -> RsgMenuItem {
+    return RsgMenuItem::new(RsgMenuKind::Separator, String::new());
+}
+pub fn submenu(label: impl Into<String>, items: impl IntoIterator<Item = RsgMenuItem>) -> RsgMenuItem {
+    return RsgMenuItem{
+        items: items.into_iter().collect(),
+        ..RsgMenuItem::new(RsgMenuKind::Submenu, label.into())
     }
+}
 
+// Tcl adding `item` to `menu`. `id` is what its events report, or the menu of
+// a submenu, and `var` the variable of a checkable or radio entry.
+fn menu_entry_command(menu: &str, item: &RsgMenuItem, id: &str, var: &str) -> String {
+    let label = tcl_string(&item.label);
+    let mut command = match &item.kind {
+        RsgMenuKind::Command => format!("{} add command -label {} -command {{ puts clicked-{} ; flush stdout }}", menu, label, id),
+        RsgMenuKind::Check(_) => format!("{} add checkbutton -label {} -variable {} -command {{ puts cb1b-{}-${} ; flush stdout }}",
+                                         menu, label, var, id, var),
+        RsgMenuKind::Radio(_) => format!("{} add radiobutton -label {} -variable {} -value {} -command {{ puts clicked-{} ; flush stdout }}",
+                                         menu, label, var, label, id),
+        RsgMenuKind::Separator => return format!("{} add separator", menu),
+        RsgMenuKind::Submenu => format!("{} add cascade -label {} -menu {}", menu, label, id)
+    };
+    if !item.accelerator.is_empty() {
+        command.push_str(&format!(" -accelerator {}", tcl_string(&item.accelerator)));
+    }
+    return command;
+}
+
+// Tcl setting a checkable menu entry ("true" or "false") or the radio
+// group `var` belongs to, or None for plain menu entries.
+fn menu_value_command(check: bool, var: Option<&String>, value: &str) -> Option<String> {
+    let var = var?;
+    if check {
+        return Some(format!("set {} {}", var, if value == "true" { 1 } else { 0 }));
+    }
+    return Some(format!("set {} {}", var, tcl_string(value)));
+}
+
+// The Tk event sequence for an accelerator as menus show it, e.g. "Ctrl+S"
+// gives "<Control-Key-s>". None if it names an unknown modifier.
+fn accelerator_sequence(accelerator: &str) -> Option<String> {
+    let mut parts: Vec<&str> = accelerator.split('+').collect();
+    let key = parts.pop().filter(|key| !key.is_empty())?;
+    let mut sequence = String::from("<");
+    let mut shift = false;
+    for modifier in parts {
+        match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => sequence.push_str("Control-"),
+            "alt" => sequence.push_str("Alt-"),
+            "cmd" | "command" => sequence.push_str("Command-"),
+            "shift" => { sequence.push_str("Shift-"); shift = true; }
+            _ => return None
+        }
+    }
+    // a single letter is a keysym whose case depends on shift
+    if key.chars().count() == 1 {
+        sequence.push_str(&format!("Key-{}>", if shift { key.to_uppercase() } else { key.to_lowercase() }));
+    } else {
+        sequence.push_str(&format!("Key-{}>", key));
+    }
+    return Some(sequence);
+}
+
+/// A sunken label stretched across every column of the layout, meant for the
+/// last row. Its text is changed with [`Window::set_status`].
+pub fn status_bar(status_text: impl Into<String>) -> RsgObj {
+    return RsgObj::new(RsgObjType::StatusBar, status_text.into(), RsgObjEx::default());
+}
+pub fn status_bar_ex<U>(status_text: impl Into<String>, status_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::StatusBar, status_text.into(), RsgObjEx::from(status_ex));
+}
+
+/// Mutually exclusive options shown as a row of toggle buttons, the chosen
+/// one pressed. Its value is the chosen option, the first one by default,
+/// and choosing one reports e.g. "segmented_0:::Dark" from `read()`.
+pub fn segmented(options: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return segmented_ex(options, "", RsgObjEx::default());
+}
+/// Same as [`segmented`], starting with `default` chosen unless it is empty.
+pub fn segmented_ex<U>(options: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>, segmented_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    let options: Vec<String> = options.into_iter().map(Into::into).collect();
+    let mut default = default.into();
+    if default.is_empty() {
+        default = options.first().cloned().unwrap_or_default();
+    }
+    return RsgObj{
+        options,
+        value: default,
+        ..RsgObj::new(RsgObjType::Segmented, String::from(""), RsgObjEx::from(segmented_ex))
+    }
+}
+
+/// A scrolling column of checkboxes, one per item. Its value is the checked
+/// items joined with commas, and toggling one reports e.g.
+/// "checklist_0:::Errors:::true" from `read()`. `size.1` is the height in
+/// pixels.
+pub fn checklist(items: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return checklist_ex(items, RsgObjEx::default());
+}
+pub fn checklist_ex<U>(items: impl IntoIterator<Item = impl Into<String>>, checklist_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        options: items.into_iter().map(Into::into).collect(),
+        ..RsgObj::new(RsgObjType::Checklist, String::from(""), RsgObjEx::from(checklist_ex))
+    }
+}
+
+// Tcl building a checklist in the frame `id`: a canvas scrolling an inner
+// frame of checkboxes. The array `var` holds each item's state by index and
+// its label under "label,<index>".
+fn checklist_commands(id: &str, var: &str, items: &[String], height: u64) -> Vec<String> {
+    let (canvas, inner, scrollbar) = (format!("{}.c", id), format!("{}.c.f", id), format!("{}.s", id));
+    let mut commands = vec![
+        format!("ttk::frame {}", id),
+        format!("canvas {} -height {} -highlightthickness 0 -yscrollcommand {{{} set}}", canvas, height, scrollbar),
+        format!("ttk::scrollbar {} -orient vertical -command {{{} yview}}", scrollbar, canvas),
+        format!("ttk::frame {}", inner),
+        format!("{} create window 0 0 -anchor nw -window {}", canvas, inner),
+        format!("bind {} <Configure> {{ {} configure -scrollregion [{} bbox all] -width [winfo reqwidth {}] }}", inner, canvas, canvas, inner),
+        format!("grid {} -row 0 -column 0 -sticky nsew ; grid {} -row 0 -column 1 -sticky ns", canvas, scrollbar)
+    ];
+    for (k, item) in items.iter().enumerate() {
+        commands.push(format!(
+            "set {}({}) 0 ; set {}(label,{}) {} ; ttk::checkbutton {}.r{} -text {} -variable {}({}) \
+             -command {{ puts cb1s-{}-${}(label,{}):::[expr {{${}({}) ? \"true\" : \"false\"}}] ; flush stdout }} ; grid {}.r{} -row {} -column 0 -sticky w",
+            var, k, var, k, tcl_string(item), inner, k, tcl_string(item), var, k, id, var, k, var, k, inner, k, k
+        ));
+    }
+    return commands;
+}
+
+// Tcl returning the checked items of a checklist, joined with commas.
+fn checklist_query(var: &str) -> String {
+    return format!(
+        "join [lmap k [lsort -integer [array names {} -regexp {{^[0-9]+$}}]] {{ if {{[set {}($k)]}} {{ set {}(label,$k) }} else continue }}] ,",
+        var, var, var
+    );
+}
+
+/// Radio buttons for `options` in one row, sharing one value: the label of
+/// the chosen option, `options[default_index]` to begin with, or empty if
+/// that is out of range. Choosing one reports e.g. "radio_group_0:::Large"
+/// from `read()`.
+pub fn radio_group(options: impl IntoIterator<Item = impl Into<String>>, default_index: usize) -> RsgObj {
+    return radio_group_ex(options, default_index, RsgObjEx::default());
+}
+pub fn radio_group_ex<U>(options: impl IntoIterator<Item = impl Into<String>>, default_index: usize, radio_group_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    let options: Vec<String> = options.into_iter().map(Into::into).collect();
+    return RsgObj{
+        value: options.get(default_index).cloned().unwrap_or_default(),
+        options,
+        ..RsgObj::new(RsgObjType::RadioGroup, String::from(""), RsgObjEx::from(radio_group_ex))
+    }
+}
+
+// Tcl creating option `k` of a radio group, reporting the group's new value.
+fn radio_group_command(group: &str, radio: &str, var: &str, option: &str, k: usize) -> String {
+    return format!(
+        "ttk::radiobutton {} -text {} -value {} -variable {} -command {{ puts cb1s-{}-[set {}] ; flush stdout }} ; grid {} -row 0 -column {} -sticky w",
+        radio, tcl_string(option), tcl_string(option), var, group, var, radio, k
+    );
+}
+
+/// A "Browse" button choosing a directory, starting in `initial_dir` unless
+/// it is empty. The chosen directory is its value, empty if the dialog was
+/// cancelled, and also fills the nearest input to its left in the same row.
+pub fn folder_browse(initial_dir: impl Into<String>) -> RsgObj {
+    return RsgObj{
+        value: initial_dir.into(),
+        ..RsgObj::new(RsgObjType::FolderBrowse, "Browse".to_string(), RsgObjEx::default())
+    }
+}
+pub fn folder_browse_ex<U>(initial_dir: impl Into<String>, folder_browse_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        value: initial_dir.into(),
+        ..RsgObj::new(RsgObjType::FolderBrowse, "Browse".to_string(), RsgObjEx::from(folder_browse_ex))
+    }
+}
+
+// Button command running the directory dialog for a folder browser. The
+// result, "" on cancel, goes to `var`; a chosen directory also replaces the
+// text of the `target` input.
+fn folder_browse_command(id: &str, var: &str, initial_dir: &str, target: Option<&str>, enable_events: bool) -> String {
+    let mut command = String::from("set d [tk_chooseDirectory -parent .");
+    if !initial_dir.is_empty() {
+        command.push_str(&format!(" -initialdir {}", tcl_string(initial_dir)));
+    }
+    command.push_str(&format!("] ; set {} $d", var));
+    if let Some(target) = target {
+        command.push_str(&format!(" ; if {{$d ne \"\"}} {{ {} delete 1.0 end ; {} insert end $d }}", target, target));
+    }
+    if enable_events {
+        command.push_str(&format!(" ; puts cb1s-{}-$d ; flush stdout", id));
+    }
+    return command;
+}
+
+/// A "Color" button opening the color dialog. The picked color, as
+/// "#rrggbb", is its value; cancelling keeps the previous one. See
+/// [`Window::set_color_target`] to color another element with it.
+pub fn color_chooser() -> RsgObj {
+    return RsgObj::new(RsgObjType::ColorChooser, String::from("Color"), RsgObjEx::default());
+}
+pub fn color_chooser_ex<U>(color_chooser_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj::new(RsgObjType::ColorChooser, String::from("Color"), RsgObjEx::from(color_chooser_ex));
+}
+
+/// A button showing the chosen one of a short list of `choices`, which pops
+/// up the list when clicked. Its value is the chosen choice, `default` or
+/// else the first one, and choosing one reports e.g.
+/// "option_menu_0:::Blue" from `read()`. `size.0` sets the width in
+/// characters.
+pub fn option_menu(choices: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>) -> RsgObj {
+    return option_menu_ex(choices, default, RsgObjEx::default());
+}
+pub fn option_menu_ex<U>(choices: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>, option_menu_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    let choices: Vec<String> = choices.into_iter().map(Into::into).collect();
+    let option_menu_ex = RsgObjEx::from(option_menu_ex);
+    let mut default = default.into();
+    if default.is_empty() {
+        default = String::from(option_menu_ex.default);
+    }
+    if default.is_empty() {
+        default = choices.first().cloned().unwrap_or_default();
+    }
+    return RsgObj{
+        options: choices,
+        value: default,
+        ..RsgObj::new(RsgObjType::OptionMenu, String::from(""), option_menu_ex)
+    }
+}
+
+/// A button dropping a menu of `entries` when clicked. Choosing an entry
+/// reports e.g. "Actions:::Delete" from `read()`.
+pub fn button_menu(button_text: impl Into<String>, entries: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return button_menu_ex(button_text, entries, RsgObjEx::default());
+}
+pub fn button_menu_ex<U>(button_text: impl Into<String>, entries: impl IntoIterator<Item = impl Into<String>>, button_menu_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        options: entries.into_iter().map(Into::into).collect(),
+        ..RsgObj::new(RsgObjType::ButtonMenu, button_text.into(), RsgObjEx::from(button_menu_ex))
+    }
+}
+
+// Tcl creating a menubutton and its menu, each entry reporting its label.
+fn button_menu_commands(id: &str, menu: &str, text: &str, entries: &[String]) -> Vec<String> {
+    let mut commands = vec![
+        format!("ttk::menubutton {} -text {} -menu {}", id, tcl_string(text), menu),
+        format!("menu {} -tearoff 0", menu)
+    ];
+    for entry in entries {
+        commands.push(format!("{} add command -label {} -command {{ puts {} ; flush stdout }}",
+                              menu, tcl_string(entry), tcl_string(&format!("cb1s-{}-{}", id, entry))));
+    }
+    return commands;
+}
+
+// Tcl making an element a drag source, ahead of its own bindings so that
+// clicks it reports are seen as well.
+fn drag_source_command(id: &str) -> String {
+    return format!("bindtags {} [linsert [bindtags {}] 0 RsgDrag]", id, id);
+}
+
+// Tcl marking an element as invalid, keeping the background it had to put
+// back once it is valid again. Elements without a background of their own
+// are left alone.
+fn invalid_mark_command(id: &str, invalid: bool) -> String {
+    if invalid {
+        return format!("if {{![info exists ::rsg_valid_bg({})] && ![catch {{{} cget -background}} bg]}} \
+                        {{ set ::rsg_valid_bg({}) $bg ; {} configure -background {} }}",
+                       id, id, id, id, INVALID_BACKGROUND);
+    }
+    return format!("if {{[info exists ::rsg_valid_bg({})]}} {{ {} configure -background $::rsg_valid_bg({}) ; unset ::rsg_valid_bg({}) }}",
+                   id, id, id, id);
+}
+
+const INVALID_BACKGROUND: &str = "#f8d7da";
+
+// Tcl replacing the text shown by a validation summary.
+fn summary_command(id: &str, r#type: &RsgObjType, text: &str) -> String {
+    match r#type {
+        RsgObjType::Output | RsgObjType::ScrolledText => {
+            return format!("{} configure -state normal ; {} delete 1.0 end ; {} insert end {} ; {} configure -state disabled",
+                           id, id, id, tcl_string(text), id);
+        }
+        _ => return format!("{} configure -text {}", id, tcl_string(text))
+    }
+}
+
+// Tcl creating an option menu on `var`, each entry reporting the new choice.
+fn option_menu_command(id: &str, var: &str, choices: &[String]) -> String {
+    let quoted: Vec<String> = choices.iter().map(|c| tcl_string(c)).collect();
+    return format!(
+        "set m [tk_optionMenu {} {} {}] ; for {{set k 0}} {{$k <= [$m index end]}} {{incr k}} {{ $m entryconfigure $k -command {{puts cb1s-{}-[set {}] ; flush stdout}} }}",
+        id, var, quoted.join(" "), id, var
+    );
+}
+
+// Button command running the color dialog for a color chooser. Only a picked
+// color replaces the value in `var` and becomes the background of `target`.
+fn color_chooser_command(id: &str, var: &str, target: Option<&str>, enable_events: bool) -> String {
+    let mut command = format!(
+        "set c [if {{[set {}] eq \"\"}} {{tk_chooseColor -parent .}} {{tk_chooseColor -parent . -initialcolor [set {}]}}] ; \
+         if {{$c ne \"\"}} {{ set {} $c",
+        var, var, var
+    );
+    if let Some(target) = target {
+        command.push_str(&format!(" ; catch {{ {} configure -background $c }}", target));
+    }
+    if enable_events {
+        command.push_str(&format!(" ; puts cb1s-{}-$c ; flush stdout", id));
+    }
+    command.push_str(" }");
+    return command;
+}
+
+/// A "label: value [copy]" row, showing the value in a read-only entry that
+/// can be selected or copied to the clipboard. `size.0` sets the width of
+/// the label and `size.1` that of the value, in characters, so that a stack
+/// of rows lines up.
+pub fn kv(kv_label: impl Into<String>, kv_value: impl Into<String>) -> RsgObj {
+    return RsgObj{
+        value: kv_value.into(),
+        ..RsgObj::new(RsgObjType::Kv, kv_label.into(), RsgObjEx::default())
+    }
+}
+pub fn kv_ex<U>(kv_label: impl Into<String>, kv_value: impl Into<String>, kv_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return RsgObj{
+        value: kv_value.into(),
+        ..RsgObj::new(RsgObjType::Kv, kv_label.into(), RsgObjEx::from(kv_ex))
+    }
+}
+
+
+// Layouts are taken as any rows of elements, so arrays and iterators work as
+// well as nested Vecs.
+fn collect_layout(layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> Vec<Vec<RsgObj>> {
+    return layout.into_iter().map(|row| row.into_iter().collect()).collect();
+}
+
+// Quotes text as a single Tcl word, whatever characters it contains.
+fn tcl_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' | '"' | '[' | ']' | '$' | '{' | '}' => { quoted.push('\\'); quoted.push(c); }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    return quoted;
+}
+
+// Replaces the text of a read-only entry.
+fn readonly_entry_command(id: &str, value: &str) -> String {
+    return format!("{} state !readonly ; {} delete 0 end ; {} insert 0 {} ; {} state readonly",
+                   id, id, id, tcl_string(value), id);
+}
+
+fn progress_command(id: &str, value: f64) -> String {
+    return format!("{} configure -value {}", id, value);
+}
+
+
+// A graph's Plotchart plot and what is needed to recreate it empty.
+struct Graph {
+    plot: rstk::TkXYPlot,
+    x_axis: (f64, f64, f64),
+    y_axis: (f64, f64, f64),
+    // series colors, reapplied when the graph is cleared
+    colors: Vec<(String, String)>
+}
+
+// Plotchart axis (min, max, step) for a range, with about ten ticks.
+fn graph_axis(range: (i64, u64)) -> (f64, f64, f64) {
+    let (min, max) = (range.0 as f64, range.1 as f64);
+    let step = if max > min { (max - min) / 10.0 } else { 1.0 };
+    return (min, max, step);
+}
+
+// Series plotted by `graph_plot`.
+const GRAPH_SERIES: &str = "data";
+
+// An element's value mirrored into another element, see `Window::link`.
+struct Link {
+    source: String,
+    target: String,
+    transform: Option<fn(&str) -> String>
+}
+
+// Order in which links are applied after an event from `changed`: the links
+// reachable from `changed`, breadth first, so chains are followed. Each
+// element is set at most once and the changed one not at all, so two-way
+// links do not write the value back and cycles end.
+fn link_order(links: &[(&str, &str)], changed: &str) -> Vec<usize> {
+    let mut order: Vec<usize> = vec![];
+    let mut reached: Vec<&str> = vec![changed];
+    let mut keys: std::collections::VecDeque<&str> = std::collections::VecDeque::new();
+    keys.push_back(changed);
+
+    while let Some(key) = keys.pop_front() {
+        for (n, (source, target)) in links.iter().enumerate() {
+            if *source == key && !reached.contains(target) {
+                order.push(n);
+                reached.push(target);
+                keys.push_back(target);
+            }
+        }
+    }
+    return order;
+}
+
+// Pad of an element: its own, unless it was left at the default, in which
+// case the window's `element_gap`.
+fn element_pad(pad: (u64, u64), element_gap: (u64, u64)) -> (u64, u64) {
+    return if pad == RsgObjEx::default().pad { element_gap } else { pad };
+}
+
+// Elements the window's `min_touch_size` applies to.
+fn is_touch_target(r#type: &RsgObjType) -> bool {
+    return matches!(r#type, RsgObjType::Button | RsgObjType::CheckBox | RsgObjType::Radio | RsgObjType::Slider
+                          | RsgObjType::Spin | RsgObjType::OptionMenu | RsgObjType::ButtonMenu
+                          | RsgObjType::FolderBrowse | RsgObjType::ColorChooser);
+}
+
+// Tcl growing the element to at least `size` pixels through grid's internal
+// pad, which leaves the cells of its neighbours alone. It runs once Tk has
+// worked out the element's own size.
+fn min_size_command(id: &str, size: (u64, u64)) -> String {
+    return format!(
+        "after idle {{ if {{[winfo exists {}]}} {{ grid configure {} -ipadx [expr {{max(0, ({} - [winfo reqwidth {}] + 1) / 2)}}] \
+         -ipady [expr {{max(0, ({} - [winfo reqheight {}] + 1) / 2)}}] }} }}",
+        id, id, size.0, id, size.1, id
+    );
+}
+
+// Compact mode shrinks the standard fonts by this factor and halves pads.
+const COMPACT_FONT_SCALE: f64 = 0.8;
+const COMPACT_FONTS: [&str; 5] = ["TkDefaultFont", "TkTextFont", "TkFixedFont", "TkHeadingFont", "TkCaptionFont"];
+
+// Tcl commands moving an element into (or back out of) compact mode, given
+// the pad it was built with. Separators lose their pad entirely.
+fn compact_commands(id: &str, r#type: &RsgObjType, pad: (u64, u64), hide: bool, compact: bool) -> Vec<String> {
+    let mut commands: Vec<String> = vec![];
+    if compact {
+        let pad = match r#type {
+            RsgObjType::Separator => (0, 0),
+            _ => (pad.0 / 2, pad.1 / 2)
+        };
+        commands.push(format!("grid configure {} -padx {} -pady {}", id, pad.0, pad.1));
+        if hide { commands.push(format!("grid remove {}", id)); }
+    } else {
+        // grid brings a removed slave back with the options it had
+        if hide { commands.push(format!("grid {}", id)); }
+        commands.push(format!("grid configure {} -padx {} -pady {}", id, pad.0, pad.1));
+    }
+    return commands;
+}
+
+// Tcl scaling a standard font by `scale`, keeping its unit.
+fn font_scale_command(font: &str, scale: f64) -> String {
+    return format!("font configure {} -size [expr {{round([font configure {} -size] * {})}}]", font, font, scale);
+}
+
+// Font size in compact mode. Tk sizes are points when positive and pixels
+// when negative, so the sign is kept.
+fn compact_font_size(size: i64) -> i64 {
+    let scaled = ((size.abs() as f64) * COMPACT_FONT_SCALE).round().max(1.0) as i64;
+    return if size < 0 { -scaled } else { scaled };
+}
+
+
+// Click-to-edit for text elements: an entry is placed over the label, sized
+// to the label's requested size so the grid cell does not change. Return or
+// leaving the entry commits, Escape cancels.
+const EDIT_LABEL_PROCS: &str = "proc rsg_edit_label {w} {
+    if {[info exists ::rsg_editing($w)]} return
+    set ::rsg_editing($w) 1
+    set e ${w}_edit
+    ttk::entry $e
+    $e insert 0 [$w cget -text]
+    place $e -in $w -x 0 -y 0 -width [winfo reqwidth $w] -height [winfo reqheight $w]
+    focus $e
+    bind $e <Return> [list rsg_edit_done $w 1]
+    bind $e <FocusOut> [list rsg_edit_done $w 1]
+    bind $e <Escape> [list rsg_edit_done $w 0]
+}
+proc rsg_edit_done {w commit} {
+    if {![info exists ::rsg_editing($w)]} return
+    unset ::rsg_editing($w)
+    set e ${w}_edit
+    if {$commit} {
+        $w configure -text [$e get]
+        puts cb1s-$w-edited
+        flush stdout
+    }
+    destroy $e
+}";
+
+
+// Focus-out check of number inputs: a sign or point on its own is cleared,
+// numbers out of range are replaced by the nearest bound.
+const NUMBER_PROCS: &str = "proc rsg_number_clamp {w min max} {
+    set v [string map {, .} [$w get]]
+    if {$v eq {}} return
+    if {![string is double -strict $v]} { $w delete 0 end ; return }
+    if {$v < $min} { set v $min } elseif {$v > $max} { set v $max } else return
+    $w delete 0 end
+    $w insert 0 $v
+}";
+
+// Borders of elements, set as fonts are: through the widget's options, or a
+// style of its own for ttk widgets without them.
+const BORDER_PROCS: &str = "proc rsg_border {w width relief} {
+    if {[catch {$w configure -borderwidth $width -relief $relief}]} {
+        set style Rsg[string map {. _} $w].[winfo class $w]
+        ttk::style configure $style -borderwidth $width -relief $relief
+        catch {$w configure -style $style}
+    }
+}";
+
+// Hover colors: the active colors of classic Tk widgets, or the colors of the
+// "active" state in the widget's own style for ttk ones. An empty color is
+// left alone.
+const HOVER_PROCS: &str = "proc rsg_hover {w fg bg} {
+    if {![catch {$w cget -activebackground}]} {
+        if {$fg ne {}} { catch {$w configure -activeforeground $fg} }
+        if {$bg ne {}} { $w configure -activebackground $bg }
+        return
+    }
+    set style Rsg[string map {. _} $w].[winfo class $w]
+    if {$fg ne {}} { ttk::style map $style -foreground [list active $fg] }
+    if {$bg ne {}} { ttk::style map $style -background [list active $bg] }
+    catch {$w configure -style $style}
+}";
+
+fn hover_command(id: &str, hover: (RsgColor, RsgColor)) -> Option<String> {
+    if let (RsgColor::None, RsgColor::None) = hover {
+        return None;
+    }
+    let color = |c: RsgColor| if let RsgColor::None = c { String::from("{}") } else { format!("{{{}}}", get_rsg_color(c)) };
+    return Some(format!("rsg_hover {} {} {}", id, color(hover.0), color(hover.1)));
+}
+
+fn border_command(id: &str, border: (u64, RsgRelief)) -> String {
+    let relief = match border.1 {
+        RsgRelief::Flat => "flat",
+        RsgRelief::Raised => "raised",
+        RsgRelief::Sunken => "sunken",
+        RsgRelief::Groove => "groove",
+        RsgRelief::Ridge => "ridge"
+    };
+    return format!("rsg_border {} {} {}", id, border.0, relief);
+}
+
+// Tooltips: resting the pointer on an element with an entry in ::rsg_tooltips
+// shows its text in a borderless toplevel after a short delay; leaving or
+// clicking the element hides it. Nothing is printed, so events are unaffected.
+const TOOLTIP_PROCS: &str = "proc rsg_tooltip_enter {w} {
+    rsg_tooltip_leave
+    set ::rsg_tooltip_after [after 500 [list rsg_tooltip_show $w]]
+}
+proc rsg_tooltip_show {w} {
+    if {![winfo exists $w] || ![info exists ::rsg_tooltips($w)]} return
+    set t .rsg_tooltip
+    catch {destroy $t}
+    toplevel $t -bd 1 -bg black
+    wm overrideredirect $t 1
+    label $t.text -text $::rsg_tooltips($w) -bg lightyellow -fg black -justify left -padx 4 -pady 2
+    pack $t.text
+    wm geometry $t +[expr {[winfo pointerx $w] + 12}]+[expr {[winfo pointery $w] + 16}]
+}
+proc rsg_tooltip_leave {} {
+    if {[info exists ::rsg_tooltip_after]} {
+        after cancel $::rsg_tooltip_after
+        unset ::rsg_tooltip_after
+    }
+    catch {destroy .rsg_tooltip}
+}";
+
+// Tcl giving an element a tooltip, keeping its other bindings.
+fn tooltip_commands(id: &str, text: &str) -> Vec<String> {
+    return vec![
+        format!("set ::rsg_tooltips({}) {}", id, tcl_string(text)),
+        format!("bind {} <Enter> {{+rsg_tooltip_enter %W}}", id),
+        format!("bind {} <Leave> {{+rsg_tooltip_leave}}", id),
+        format!("bind {} <ButtonPress> {{+rsg_tooltip_leave}}", id)
+    ];
+}
+
+// Fonts of elements: widgets with a -font option take the font directly, ttk
+// widgets without one get a style of their own, named as the color styles.
+// Attributes not given are taken from the widget's current font.
+const FONT_PROCS: &str = "proc rsg_font {w options} {
+    if {[catch {$w cget -font} current] || $current eq {}} { set current TkDefaultFont }
+    set font [dict merge [font actual $current] $options]
+    if {[catch {$w configure -font $font}]} {
+        set style Rsg[string map {. _} $w].[winfo class $w]
+        ttk::style configure $style -font $font
+        catch {$w configure -style $style}
+    }
+}";
+
+// Heights of texts in lines. ttk labels have no -height, so the text gets
+// vertical padding making up for the lines it lacks; run once its font is
+// set.
+const TEXT_HEIGHT_PROCS: &str = "proc rsg_text_height {w lines} {
+    set font [$w cget -font]
+    if {$font eq {}} { set font [ttk::style lookup [$w cget -style] -font] }
+    if {$font eq {}} { set font [ttk::style lookup TLabel -font] }
+    if {$font eq {}} { set font TkDefaultFont }
+    set shown [llength [split [$w cget -text] \\n]]
+    set extra [expr {max(0, $lines - $shown) * [font metrics $font -linespace]}]
+    $w configure -padding [list 0 [expr {$extra / 2}] 0 [expr {$extra - $extra / 2}]]
+}";
+
+// Tcl giving the element `font`, leaving the attributes it does not set.
+fn font_command(id: &str, font: RsgFont) -> String {
+    let mut options = String::from("list");
+    if !font.family.is_empty() { options.push_str(&format!(" -family {}", tcl_string(font.family))); }
+    if font.size != 0 { options.push_str(&format!(" -size {}", font.size)); }
+    if font.bold { options.push_str(" -weight bold"); }
+    if font.italic { options.push_str(" -slant italic"); }
+    if font.underline { options.push_str(" -underline 1"); }
+    return format!("rsg_font {} [{}]", id, options);
+}
+
+// Character limits of inputs. Text widgets have no validation, so keys adding
+// a character to a full input are stopped before the Text bindings see them,
+// and pastes insert only as much of the clipboard as fits.
+const CHAR_LIMIT_PROCS: &str = "proc rsg_char_room {w limit char} {
+    if {$char eq {} || ([string is control $char] && $char ni [list \r \t])} { return 1 }
+    if {[$w tag ranges sel] ne {}} { return 1 }
+    return [expr {[string length [$w get 1.0 end-1c]] < $limit}]
+}
+proc rsg_char_paste {w limit} {
+    if {[catch {clipboard get -displayof $w} text]} return
+    catch {$w delete sel.first sel.last}
+    set room [expr {$limit - [string length [$w get 1.0 end-1c]]}]
+    if {$room > 0} { $w insert insert [string range $text 0 [expr {$room - 1}]] }
+    $w see insert
+}";
+
+fn char_limit_commands(id: &str, limit: u64) -> Vec<String> {
+    return vec![
+        format!("bind {} <KeyPress> {{+if {{![rsg_char_room %W {} %A]}} break}}", id, limit),
+        format!("bind {} <<Paste>> {{rsg_char_paste %W {} ; break}}", id, limit)
+    ];
+}
+
+// Makes Enter click `button`: the toplevel's binding covers every element,
+// and inputs get their own so Enter does not add a line to them first.
+// `invoke` leaves a disabled button alone.
+fn return_key_commands(root: &str, button: &str, inputs: &[String]) -> Vec<String> {
+    let mut commands = vec![format!("bind {} <Return> {{ {} invoke }}", root, button)];
+    for input in inputs {
+        commands.push(format!("bind {} <Return> {{ {} invoke ; break }}", input, button));
+    }
+    return commands;
+}
+
+// Dragging between elements: sources carry the RsgDrag bindtag, drop targets
+// are listed in ::rsg_drop_targets. The ghost label only appears once the
+// pointer has moved a few pixels, so plain clicks on a source still work; a
+// release outside every target, or Escape, drops nothing.
+const DRAG_PROCS: &str = "proc rsg_drag_press {w x y} {
+    set ::rsg_dnd [list $w $x $y]
+}
+proc rsg_drag_motion {x y} {
+    if {![info exists ::rsg_dnd] || $::rsg_dnd eq {}} return
+    lassign $::rsg_dnd w x0 y0
+    if {![winfo exists .rsg_ghost]} {
+        if {abs($x - $x0) < 5 && abs($y - $y0) < 5} return
+        toplevel .rsg_ghost
+        wm overrideredirect .rsg_ghost 1
+        pack [label .rsg_ghost.l -text [rsg_drag_text $w] -relief solid -borderwidth 1]
+    }
+    wm geometry .rsg_ghost +[expr {$x + 12}]+[expr {$y + 12}]
+}
+proc rsg_drag_release {x y} {
+    if {![winfo exists .rsg_ghost]} { set ::rsg_dnd {} ; return }
+    set w [lindex $::rsg_dnd 0]
+    rsg_drag_cancel
+    set t [winfo containing $x $y]
+    while {$t ne {} && ![info exists ::rsg_drop_targets($t)]} { set t [winfo parent $t] }
+    if {$t eq {}} return
+    set ::rsg_drop_source $w
+    set ::rsg_drop_text [rsg_drag_text $w]
+    puts cb1s-$t-dropped
+    flush stdout
+}
+proc rsg_drag_cancel {} {
+    set ::rsg_dnd {}
+    destroy .rsg_ghost
+}
+proc rsg_drag_text {w} {
+    if {[catch {$w cget -text} text]} { return {} }
+    return $text
+}
+bind RsgDrag <ButtonPress-1> {rsg_drag_press %W %X %Y}
+bind RsgDrag <B1-Motion> {rsg_drag_motion %X %Y}
+bind RsgDrag <ButtonRelease-1> {rsg_drag_release %X %Y}
+bind all <Escape> {+rsg_drag_cancel}";
+
+// Keys the source and text of a drop are listed under in the values of the
+// read() returning it.
+pub const DROP_SOURCE_KEY: &str = "drop_source";
+pub const DROP_TEXT_KEY: &str = "drop_text";
+
+
+// Dragging a titlebar: the offset of the pointer from the window's corner is
+// kept from the press, so the window moves with the pointer without jumping.
+const TITLEBAR_PROCS: &str = "proc rsg_drag_start {x y} {
+    set ::rsg_drag [list [expr {$x - [winfo x .]}] [expr {$y - [winfo y .]}]]
+}
+proc rsg_drag_move {x y} {
+    lassign $::rsg_drag dx dy
+    wm geometry . +[expr {$x - $dx}]+[expr {$y - $dy}]
+}";
+
+
+// Kiosk mode: the unlock sequence is matched against the last keys typed,
+// and hiding the cursor remembers each widget's own cursor so moving the
+// mouse restores it.
+const KIOSK_PROCS: &str = "proc rsg_kiosk_key {key unlock} {
+    if {$key eq \"\"} return
+    append ::rsg_kiosk_typed $key
+    set ::rsg_kiosk_typed [string range $::rsg_kiosk_typed end-[expr {[string length $unlock] - 1}] end]
+    if {$::rsg_kiosk_typed eq $unlock} {
+        set ::rsg_kiosk_typed {}
+        puts close-request
+        flush stdout
+    }
+}
+proc rsg_kiosk_widgets {w} {
+    set all [list $w]
+    foreach c [winfo children $w] { lappend all {*}[rsg_kiosk_widgets $c] }
+    return $all
+}
+proc rsg_kiosk_hide {} {
+    foreach w [rsg_kiosk_widgets .] {
+        if {![info exists ::rsg_kiosk_cursor($w)] && ![catch {$w cget -cursor} c]} {
+            set ::rsg_kiosk_cursor($w) $c
+            $w configure -cursor none
+        }
+    }
+}
+proc rsg_kiosk_show {ms} {
+    foreach {w c} [array get ::rsg_kiosk_cursor] { catch {$w configure -cursor $c} }
+    array unset ::rsg_kiosk_cursor
+    after cancel rsg_kiosk_hide
+    after $ms rsg_kiosk_hide
+}";
+
+// Tcl locking the window down in kiosk mode. Closing is suppressed at the
+// protocol level, which also covers Alt-F4, while the unlock sequence is
+// bound on the "all" tag so it is seen whichever widget has the focus; it
+// closes through "close-request", so unsaved changes are still confirmed.
+// The window stays managed rather than override-redirect, which would keep
+// it from getting the keyboard focus on X11.
+fn kiosk_commands(unlock: &str, cursor_idle: u64) -> Vec<String> {
+    let mut commands = vec![
+        String::from("wm attributes . -fullscreen 1 -topmost 1"),
+        String::from("wm protocol . WM_DELETE_WINDOW {}")
+    ];
+    if !unlock.is_empty() {
+        commands.push(format!("bind all <KeyPress> {{+rsg_kiosk_key %A {}}}", tcl_string(&unlock.replace('%', "%%"))));
+    }
+    if cursor_idle != 0 {
+        let ms = cursor_idle * 1000;
+        commands.push(format!("bind all <Motion> {{+rsg_kiosk_show {}}}", ms));
+        commands.push(format!("after {} rsg_kiosk_hide", ms));
+    }
+    return commands;
+}
+
+fn apply_colors(id: &str, color: (RsgColor, RsgColor)) {
+    for command in plain_color_commands(id, color) {
+        rstk::tell_wish(&command);
+    }
+}
+
+fn plain_color_commands(id: &str, color: (RsgColor, RsgColor)) -> Vec<String> {
+    let mut commands: Vec<String> = vec![];
+    if let RsgColor::None = color.0 {} else {
+        commands.push(format!("{} configure -foreground {{{}}}", id, get_rsg_color(color.0)));
+    }
+    if let RsgColor::None = color.1 {} else {
+        commands.push(format!("{} configure -background {{{}}}", id, get_rsg_color(color.1)));
+    }
+    return commands;
+}
+
+// Tcl recoloring an element once the window is up, the way its colors were
+// applied when it was made, or None for elements taking no colors.
+fn recolor_commands(id: &str, r#type: &RsgObjType, color: (RsgColor, RsgColor), respect_option_db: bool) -> Option<Vec<String>> {
+    match r#type {
+        RsgObjType::Text | RsgObjType::Button | RsgObjType::CheckBox | RsgObjType::Radio => {
+            return Some(color_commands(id, color, ("fg", "bg"), respect_option_db));
+        }
+        RsgObjType::Input | RsgObjType::Slider => {
+            return Some(color_commands(id, color, ("bg", "fg"), respect_option_db));
+        }
+        RsgObjType::NumberInput | RsgObjType::Spin | RsgObjType::Output | RsgObjType::ScrolledText | RsgObjType::StatusBar |
+        RsgObjType::OptionMenu | RsgObjType::ButtonMenu | RsgObjType::FolderBrowse | RsgObjType::ColorChooser => {
+            return Some(plain_color_commands(id, color));
+        }
+        _ => return None
+    }
+}
+
+// Whether a color was set, rather than left to the theme.
+fn is_set(color: RsgColor) -> bool {
+    return !matches!(color, RsgColor::None);
+}
+
+// Tcl configuring the colors of a classic Tk element. Each color set on the
+// element also becomes the opposite active color. When only one is set, the
+// other active color follows the element's own `mirror` option (e.g. "fg",
+// "bg"), unless `respect_option_db` leaves it to the option database.
+fn color_commands(id: &str, color: (RsgColor, RsgColor), mirror: (&str, &str), respect_option_db: bool) -> Vec<String> {
+    let mut commands: Vec<String> = vec![];
+    let fg_set = is_set(color.0);
+    let bg_set = is_set(color.1);
+
+    if fg_set {
+        commands.push(format!("{} configure -fg {{{}}}", id, get_rsg_color(color.0)));
+        commands.push(format!("{} configure -activebackground {{{}}}", id, get_rsg_color(color.0)));
+    } else if bg_set && !respect_option_db {
+        commands.push(format!("{} configure -activebackground [{} cget -{}]", id, id, mirror.0));
+    }
+
+    if bg_set {
+        commands.push(format!("{} configure -bg {{{}}}", id, get_rsg_color(color.1)));
+        commands.push(format!("{} configure -activeforeground {{{}}}", id, get_rsg_color(color.1)));
+    } else if fg_set && !respect_option_db {
+        commands.push(format!("{} configure -activeforeground [{} cget -{}]", id, id, mirror.1));
+    }
+    return commands;
+}
+
+// Tcl making a button report the mouse button and the Tk modifier state
+// along with its clicks, as "clicked-<id> <button> <state>". The left button
+// keeps going through -command, so a press released outside the button is
+// still no click; with `enable_events` the middle and right buttons report
+// clicks too.
+fn click_commands(id: &str, enable_events: bool) -> Vec<String> {
+    let mut commands = vec![
+        format!("bind {} <ButtonPress-1> {{ set ::rsg_state({}) %s }}", id, id),
+        format!("{} configure -command {{ puts \"clicked-{} 1 [expr {{[info exists ::rsg_state({})] ? $::rsg_state({}) : 0}}]\" ; \
+                 unset -nocomplain ::rsg_state({}) ; flush stdout }}", id, id, id, id, id)
+    ];
+    if enable_events {
+        for button in [2, 3] {
+            commands.push(format!("bind {} <ButtonRelease-{}> {{ puts \"clicked-{} %b %s\" ; flush stdout }}", id, button, id));
+        }
+    }
+    return commands;
+}
+
+// Splits a click event into the widget id, mouse button and modifiers;
+// other events count as a plain left click.
+fn parse_click(event: &str) -> (&str, u8, RsgModifiers) {
+    let mut parts = event.split_whitespace();
+    let id = parts.next().unwrap_or("");
+    let button = parts.next().and_then(|b| b.parse().ok()).unwrap_or(1);
+    let state = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    return (id, button, RsgModifiers::from_tk_state(state));
+}
+
+// Splits an event from mainloop into the widget id, the value it carries,
+// and the mouse button and modifiers of a click. Any text is accepted: an
+// event that does not make sense names no known widget.
+fn split_event(event: &str) -> (&str, Option<&str>, u8, RsgModifiers) {
+    match event.split_once("-cbsep-") {
+        Some((id, value)) => return (id.trim(), Some(value.trim()), 1, RsgModifiers::default()),
+        None => {
+            let (id, button, modifiers) = parse_click(event);
+            return (id, None, button, modifiers);
+        }
+    }
+}
+
+// Tcl creating row `index` of a bound list, reporting clicks with the index.
+fn list_row_command(id: &str, index: usize, text: &str) -> String {
+    return format!("label {} -text {} ; grid {} -row {} -column 0 -sticky w ; bind {} <Button-1> {{ puts cb1s-{}-{} ; flush stdout }}",
+                   id, tcl_string(text), id, index, id, id, index);
+}
+
+fn blend(from: (u8, u8, u8), to: (u8, u8, u8), factor: f64) -> String {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * factor).round() as u8;
+    return format!("#{:02x}{:02x}{:02x}", mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2));
+}
+
+// Tcl commands moving an element into (or back out of) the disabled state.
+//
+// Custom colors would otherwise stay vivid and make a disabled element look
+// enabled, so they are muted towards the background by `factor` while
+// disabled. Elements colored through widget options get the muted colors
+// configured directly and the originals restored on enable; ttk check and
+// radio buttons get a derived style whose map only applies while disabled.
+// `rgb` resolves a color to its red, green and blue components.
+fn state_commands(id: &str, r#type: &RsgObjType, color: (RsgColor, RsgColor), rgb: &dyn Fn(RsgColor) -> (u8, u8, u8),
+                  background: (u8, u8, u8), factor: f64, disabled: bool) -> Vec<String> {
+    let mut commands: Vec<String> = vec![];
+    let fg_set = is_set(color.0);
+    let bg_set = is_set(color.1);
+    let fg_target = if bg_set { rgb(color.1) } else { background };
+
+    match r#type {
+        RsgObjType::Button | RsgObjType::Input | RsgObjType::OptionMenu | RsgObjType::Slider => {
+            commands.push(format!("{} configure -state {}", id, if disabled { "disabled" } else { "normal" }));
+        }
+        _ => {
+            commands.push(format!("{} state {}", id, if disabled { "disabled" } else { "!disabled" }));
+        }
+    }
+
+    match r#type {
+        RsgObjType::CheckBox | RsgObjType::Radio if disabled && (fg_set || bg_set) => {
+            let class = if let RsgObjType::CheckBox = r#type { "TCheckbutton" } else { "TRadiobutton" };
+            let style = format!("Rsg{}.{}", id.replace('.', "_"), class);
+            let mut map = format!("ttk::style map {}", style);
+            if fg_set { map.push_str(&format!(" -foreground [list disabled {}]", blend(rgb(color.0), fg_target, factor))); }
+            if bg_set { map.push_str(&format!(" -background [list disabled {}]", blend(rgb(color.1), background, factor))); }
+            commands.insert(0, map);
+            commands.insert(1, format!("{} configure -style {}", id, style));
+        }
+        RsgObjType::Text | RsgObjType::Button | RsgObjType::Input | RsgObjType::Spin | RsgObjType::Image => {
+            if fg_set {
+                if disabled {
+                    let muted = blend(rgb(color.0), fg_target, factor);
+                    commands.push(format!("{} configure -foreground {}", id, muted));
+                    if let RsgObjType::Button = r#type {
+                        commands.push(format!("{} configure -disabledforeground {}", id, muted));
+                    }
+                } else {
+                    commands.push(format!("{} configure -foreground {{{}}}", id, get_rsg_color(color.0)));
+                }
+            }
+            if bg_set {
+                if disabled {
+                    commands.push(format!("{} configure -background {}", id, blend(rgb(color.1), background, factor)));
+                } else {
+                    commands.push(format!("{} configure -background {{{}}}", id, get_rsg_color(color.1)));
+                }
+            }
+        }
+        _ => {}
+    }
+    return commands;
+}
+
+// Asks Tk for the components of a color, scaled down to 8 bits.
+fn tk_rgb(color: &str) -> (u8, u8, u8) {
+    let result = rstk::ask_wish(&format!("puts [winfo rgb . {{{}}}] ; flush stdout", color));
+    let parts: Vec<u8> = result.split_whitespace().map(|p| (p.parse::<u32>().unwrap_or(0) >> 8) as u8).collect();
+    if parts.len() != 3 { return (0, 0, 0); }
+    return (parts[0], parts[1], parts[2]);
+}
+
+
+
+fn key_prefix(r#type: &RsgObjType) -> &'static str {
+    match r#type {
+        RsgObjType::Text => return "text",
+        RsgObjType::Button => return "button",
+        RsgObjType::CheckBox => return "checkbox",
+        RsgObjType::Radio => return "radio",
+        RsgObjType::Input => return "input",
+        RsgObjType::Slider => return "slider",
+        RsgObjType::Separator => return "separator",
+        RsgObjType::Spin => return "spin",
+        RsgObjType::Progress => return "progress",
+        RsgObjType::Image => return "image",
+        RsgObjType::Frame => return "frame",
+        RsgObjType::Column => return "column",
+        RsgObjType::Kv => return "kv",
+        RsgObjType::Canvas => return "canvas",
+        RsgObjType::Graph => return "graph",
+        RsgObjType::Output => return "output",
+        RsgObjType::StatusBar => return "status_bar",
+        RsgObjType::Segmented => return "segmented",
+        RsgObjType::RadioGroup => return "radio_group",
+        RsgObjType::Gauge => return "gauge",
+        RsgObjType::NumberInput => return "number_input",
+        RsgObjType::Checklist => return "checklist",
+        RsgObjType::FolderBrowse => return "folder_browse",
+        RsgObjType::ColorChooser => return "color_chooser",
+        RsgObjType::OptionMenu => return "option_menu",
+        RsgObjType::SizeGrip => return "sizegrip",
+        RsgObjType::ScrolledText => return "scrolled_text",
+        RsgObjType::TitleBar => return "titlebar",
+        RsgObjType::ButtonMenu => return "button_menu",
+        RsgObjType::Paned => return "paned",
+    }
+}
+
+// Keys address elements in Window methods and the keyed values. Elements with
+// an explicit key are keyed by it. Elements that show their name (text,
+// buttons, button menus, checkboxes, radios, kv rows) are keyed by it; every
+// other element gets "<type>_<n>", numbered per type in layout order. A key
+// that is already taken gets a "_<n>" suffix, so keys are always unique and
+// depend only on the layout.
+//
+// Keys are returned in layout order, each container directly followed by the
+// keys of its own layout.
+fn assign_keys(layout: &Vec<Vec<RsgObj>>) -> Vec<String> {
+    return assign_keys_with_bases(layout).into_iter().map(|(_, key)| key).collect();
+}
+
+// Like `assign_keys`, but pairs each key with the one the element asked for.
+fn assign_keys_with_bases(layout: &Vec<Vec<RsgObj>>) -> Vec<(String, String)> {
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut counters: HashMap<&str, u64> = HashMap::new();
+    let mut keys: Vec<(String, String)> = vec![];
+    assign_keys_into(layout, &mut taken, &mut counters, &mut keys);
+    return keys;
+}
+
+fn assign_keys_into(layout: &Vec<Vec<RsgObj>>, taken: &mut HashSet<String>,
+                    counters: &mut HashMap<&str, u64>, keys: &mut Vec<(String, String)>) {
+    for row in layout {
+        for x in row {
+            let named = match x.r#type {
+                RsgObjType::Text | RsgObjType::Button | RsgObjType::ButtonMenu | RsgObjType::CheckBox | RsgObjType::Radio | RsgObjType::Kv => !x.name.is_empty(),
+                _ => false
+            };
+
+            let base: String;
+            if let Some(explicit) = &x.key {
+                base = explicit.clone();
+            } else if named {
+                base = x.name.clone();
+            } else {
+                let prefix = key_prefix(&x.r#type);
+                let counter = counters.entry(prefix).or_insert(0);
+                base = format!("{}_{}", prefix, counter);
+                *counter += 1;
+            }
+
+            let mut key = base.clone();
+            let mut suffix = 1;
+            while taken.contains(&key) {
+                key = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+
+            taken.insert(key.clone());
+            keys.push((base, key));
+
+            assign_keys_into(&x.layout, taken, counters, keys);
+        }
+    }
+}
+
+// Where warnings go, see set_warning_hook.
+static WARNING_HOOK: Mutex<Option<fn(&str)>> = Mutex::new(None);
+
+/// Sends the library's warnings, such as the issues `window()` finds with
+/// [`validate_layout`], to `hook` instead of printing them to stderr as
+/// "rsg: warning: <message>". `None` restores printing; a hook doing
+/// nothing silences them.
+pub fn set_warning_hook(hook: Option<fn(&str)>) {
+    *WARNING_HOOK.lock().unwrap() = hook;
+}
+
+fn warn(message: &str) {
+    let hook = *WARNING_HOOK.lock().unwrap();
+    match hook {
+        Some(hook) => hook(message),
+        None => eprintln!("rsg: warning: {}", message)
+    }
+}
+
+/// Checks a layout for misconfigurations that would otherwise only show up
+/// as visual glitches. `window()` runs this and reports the issues as
+/// warnings, see [`set_warning_hook`]; `window_strict()` refuses to open
+/// the window instead.
+pub fn validate_layout(layout: &Vec<Vec<RsgObj>>) -> Vec<LayoutIssue> {
+    let mut keys = assign_keys_with_bases(layout).into_iter();
+    let mut groups: HashMap<String, usize> = HashMap::new();
+    count_radio_groups(layout, &mut groups);
+    let mut issues: Vec<LayoutIssue> = vec![];
+    validate_into(layout, None, &mut keys, &groups, &mut issues);
+    return issues;
+}
+
+// How many radios use each explicit group name, across the whole layout.
+fn count_radio_groups(layout: &[Vec<RsgObj>], groups: &mut HashMap<String, usize>) {
+    for x in layout.iter().flatten() {
+        if let (RsgObjType::Radio, Some(group)) = (&x.r#type, &x.group) {
+            *groups.entry(group.clone()).or_insert(0) += 1;
+        }
+        count_radio_groups(&x.layout, groups);
+    }
+}
+
+fn validate_into(layout: &[Vec<RsgObj>], container: Option<&String>, keys: &mut std::vec::IntoIter<(String, String)>,
+                 groups: &HashMap<String, usize>, issues: &mut Vec<LayoutIssue>) {
+    let collisions = span_collisions(layout);
+    for (i, row) in layout.iter().enumerate() {
+        for (j, x) in row.iter().enumerate() {
+            let (base, key) = keys.next().unwrap();
+            let mut issue = |kind: LayoutIssueKind| issues.push(LayoutIssue{
+                row: i, column: j, container: container.cloned(), kind
+            });
+
+            if base != key {
+                issue(LayoutIssueKind::DuplicateKey{ key: base, assigned: key.clone() });
+            }
+            if let RsgObjType::Image = x.r#type {
+                if (x.size.0 == 0) != (x.size.1 == 0) {
+                    issue(LayoutIssueKind::ZeroSizeImage);
+                }
+            }
+            let colored = !matches!(x.color, (RsgColor::None, RsgColor::None));
+            let shows_colors = !matches!(x.r#type, RsgObjType::Progress | RsgObjType::Frame | RsgObjType::Column
+                                                 | RsgObjType::Kv | RsgObjType::Image | RsgObjType::Graph);
+            if colored && !shows_colors {
+                issue(LayoutIssueKind::IgnoredColor);
+            }
+            if collisions.contains(&(i, j)) {
+                issue(LayoutIssueKind::SpanCollision);
+            }
+            for color in [x.color.0, x.color.1] {
+                if let RsgColor::Hex(text) = color {
+                    if !color.is_valid() { issue(LayoutIssueKind::InvalidColor{ color: text.to_string() }); }
+                }
+            }
+            if let Some(cursor) = &x.cursor {
+                if !is_cursor(cursor) { issue(LayoutIssueKind::InvalidCursor{ cursor: cursor.clone() }); }
+            }
+            match (&x.r#type, &x.group) {
+                (RsgObjType::RadioGroup, _) if x.options.is_empty() => issue(LayoutIssueKind::EmptyRadioGroup{ group: key.clone() }),
+                (RsgObjType::Radio, Some(group)) if groups[group] == 1 => issue(LayoutIssueKind::EmptyRadioGroup{ group: group.clone() }),
+                _ => {}
+            }
+
+            validate_into(&x.layout, Some(&key), keys, groups, issues);
+        }
+    }
+}
+
+
+// Positions of the elements of one grid that would overlap the cells of an
+// element placed before them, counting spans. They are left out of the grid,
+// so their own spans take nothing.
+fn span_collisions(layout: &[Vec<RsgObj>]) -> HashSet<(usize, usize)> {
+    let mut taken: HashSet<(usize, usize)> = HashSet::new();
+    let mut collisions: HashSet<(usize, usize)> = HashSet::new();
+    for (i, row) in layout.iter().enumerate() {
+        for (j, x) in row.iter().enumerate() {
+            let columns = j..j + x.span.0.max(1) as usize;
+            let rows = i..i + x.span.1.max(1) as usize;
+            let cells: Vec<(usize, usize)> = rows.flat_map(|r| columns.clone().map(move |c| (r, c))).collect();
+            if cells.iter().any(|cell| taken.contains(cell)) {
+                collisions.insert((i, j));
+            } else {
+                taken.extend(cells);
+            }
+        }
+    }
+    return collisions;
+}
+
+// Tcl stretching an element over the cells of its span, if it covers more
+// than its own. A span of 0 counts as 1, as in `span_collisions`.
+fn span_command(id: &str, span: (u64, u64)) -> Option<String> {
+    let (columns, rows) = (span.0.max(1), span.1.max(1));
+    if columns == 1 && rows == 1 {
+        return None;
+    }
+    return Some(format!("grid configure {} -columnspan {} -rowspan {}", id, columns, rows));
+}
+
+// Replaces invalid hex colors by `RsgColor::None` and drops unknown cursors,
+// so that they are reported by validate_layout instead of failing inside wish.
+fn drop_invalid_options(layout: &mut [Vec<RsgObj>]) {
+    for x in layout.iter_mut().flatten() {
+        if !x.color.0.is_valid() { x.color.0 = RsgColor::None; }
+        if !x.color.1.is_valid() { x.color.1 = RsgColor::None; }
+        if !x.cursor.as_deref().is_none_or(is_cursor) { x.cursor = None; }
+        drop_invalid_options(&mut x.layout);
+    }
+}
+
+
+// An event hook, see `Window::on_event`.
+type Hook = Box<dyn Fn(&RsgEvent) + Send>;
+
+// A check of an element's value, see `Window::add_validator`.
+type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send>;
+
+pub struct Window{
+    registry: registry::Registry,
+    inputs: Vec<String>,
+    name: String,
+    layout: Vec<Vec<RsgObj>>,
+    root: rstk::TkTopLevel,
+    sliders: Vec<String>,
+    spins: Vec<String>,
+    editables: Vec<String>,
+    kvs: Vec<String>,
+    kv_entries: HashMap<String, String>,
+    segments: Vec<String>,
+    radio_groups: Vec<String>,
+    checklists: Vec<String>,
+    checkboxes: Vec<String>,
+    radios: Vec<String>,
+    option_menus: Vec<String>,
+    image_sizes: HashMap<String, (u64, u64)>,
+    // photo images shown on buttons, deleted when the layout is swapped
+    button_images: Vec<String>,
+    // photo image of the window's icon
+    icon: Option<String>,
+    folder_browses: Vec<String>,
+    color_choosers: Vec<String>,
+    color_chooser_events: HashSet<String>,
+    // Tcl variables holding the value of segmented bars, folder browsers
+    // and color choosers
+    value_vars: HashMap<String, String>,
+    element_types: HashMap<String, RsgObjType>,
+    element_colors: HashMap<String, (RsgColor, RsgColor)>,
+    element_metadata: HashMap<String, String>,
+    // checks of inputs made by input_validated, and the ids of those failing
+    // them when values were last read
+    checks: HashMap<String, Check>,
+    failing_checks: RefCell<HashSet<String>>,
+    disabled_blend: f64,
+    element_gap: (u64, u64),
+    min_touch_size: Option<(u64, u64)>,
+    respect_option_db: bool,
+    kiosk: bool,
+    element_pads: HashMap<String, (u64, u64)>,
+    compact_hidden: HashSet<String>,
+    // ids of elements hidden with set_visible, or built invisible
+    hidden: HashSet<String>,
+    graphs: HashMap<String, Graph>,
+    gauges: HashMap<String, Gauge>,
+    links: Vec<Link>,
+    outputs: HashMap<String, u64>,
+    status_bar: Option<String>,
+    // menu bar entries, by id: the menu holding them and their index; their
+    // ids by path; the menus of submenus by path, "" being the menu bar
+    menu_entries: HashMap<String, (String, u64)>,
+    menu_paths: HashMap<String, String>,
+    submenus: HashMap<String, String>,
+    // value-bearing menu entries and radio groups, checkable entries, and
+    // the id each radio group's value is kept under
+    menu_values: Vec<String>,
+    menu_checks: HashSet<String>,
+    menu_groups: HashMap<String, String>,
+    // frame and row labels of each bound list, by key prefix
+    lists: HashMap<String, (String, Vec<String>)>,
+    // keys left out of dirty tracking, and the values at the last mark_clean
+    untracked: HashSet<String>,
+    clean_values: HashMap<String, String>,
+    // everything sent to wish while building the window
+    script: Vec<String>,
+    // named fonts and their sizes before compact mode shrank them
+    normal_font_sizes: Vec<(String, i64)>,
+    hooks: HashMap<String, Vec<Hook>>,
+    // validators by key, the keys currently marked invalid, and the element
+    // listing the messages of the last validate_all
+    validators: HashMap<String, Vec<Validator>>,
+    invalid: RefCell<HashSet<String>>,
+    validation_summary: Option<String>,
+    handle: OnceLock<WindowHandle>,
+    // ids of widgets destroyed by swap_layout
+    retired: HashSet<String>,
+    // set once read() has reported WIN_CLOSED; values are then answered
+    // from those last read rather than from wish
+    closed: Cell<bool>,
+    cached_values: RefCell<Vec<(String, String)>>,
+    // elements accepting drops, and the source key and text of the drop the
+    // last read() returned
+    drop_targets: HashSet<String>,
+    last_drop: RefCell<Option<(String, String)>>,
+    // ids of disabled elements, whose events read() drops
+    disabled: RefCell<HashSet<String>>,
+    // the button Enter clicks, see RsgObjEx::bind_return_key
+    return_key_button: Option<String>,
+    // the element given the focus once the window is built
+    focus_target: Option<String>,
+    // number of the last timeout asked of wish; the timers of earlier reads
+    // may still fire and are ignored
+    timeouts: Cell<u64>,
+    events: Arc<handle::EventQueue>
+}
+
+
+pub fn window(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> Window {
+    return window_ex(window_name, layout, RsgWindowEx::default());
+}
+pub fn window_ex<V>(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, window_ex: V) -> Window where RsgWindowEx: From<V> {
+    let layout = collect_layout(layout);
+    for issue in validate_layout(&layout) {
+        warn(&issue.to_string());
+    }
+    return build_window(window_name.into(), layout, RsgWindowEx::from(window_ex));
+}
+
+/// Like [`window`], opening the window at `location` on the screen, see
+/// [`RsgWindowEx::location`].
+pub fn window_at(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, location: (i64, i64)) -> Window {
+    return window_ex(window_name, layout, RsgWindowEx{ location: Some(location), ..Default::default() });
+}
+
+/// Like [`window`], but returns every issue found by [`validate_layout`] as
+/// an error instead of opening a window with them.
+pub fn window_strict(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> Result<Window, RsgError> {
+    return window_strict_ex(window_name, layout, RsgWindowEx::default());
+}
+pub fn window_strict_ex<V>(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, window_ex: V) -> Result<Window, RsgError> where RsgWindowEx: From<V> {
+    let layout = collect_layout(layout);
+    let issues = validate_layout(&layout);
+    if !issues.is_empty() {
+        return Err(RsgError::InvalidLayout(issues));
+    }
+    return Ok(build_window(window_name.into(), layout, RsgWindowEx::from(window_ex)));
+}
+
+fn build_window(window_name: String, layout: Vec<Vec<RsgObj>>, window_ex: RsgWindowEx) -> Window {
+    let root = rstk::start_wish().unwrap();
+    let mut new = blank_window(window_name, layout, root, window_ex, Arc::new(handle::EventQueue::default()));
+
+    rstk::start_recording();
+    rstk::tell_wish(EDIT_LABEL_PROCS);
+    rstk::tell_wish(TITLEBAR_PROCS);
+    rstk::tell_wish(DRAG_PROCS);
+    rstk::tell_wish(NUMBER_PROCS);
+    rstk::tell_wish(FONT_PROCS);
+    rstk::tell_wish(TEXT_HEIGHT_PROCS);
+    rstk::tell_wish(TOOLTIP_PROCS);
+    rstk::tell_wish(BORDER_PROCS);
+    rstk::tell_wish(HOVER_PROCS);
+    rstk::tell_wish(CHAR_LIMIT_PROCS);
+    rstk::tell_wish(ATTENTION_PROCS);
+    rstk::tell_wish(GAUGE_PROCS);
+    rstk::tell_wish(&title_command(new.root.id(), &new.name));
+    if let Some(size) = window_ex.size {
+        rstk::tell_wish(&size_command(new.root.id(), size));
+    }
+    if let Some(location) = window_ex.location {
+        rstk::tell_wish(&location_command(new.root.id(), location));
+    }
+    rstk::tell_wish(&format!("wm resizable {} {} {}", new.root.id(), window_ex.resizable.0 as u8, window_ex.resizable.1 as u8));
+    if window_ex.keep_on_top {
+        rstk::tell_wish(&format!("wm attributes {} -topmost 1", new.root.id()));
+    }
+    for command in restore_event_commands(new.root.id()) {
+        rstk::tell_wish(&command);
+    }
+    if let Some(size) = window_ex.min_size {
+        rstk::tell_wish(&format!("wm minsize {} {} {}", new.root.id(), size.0, size.1));
+    }
+    if let Some(size) = window_ex.max_size {
+        rstk::tell_wish(&format!("wm maxsize {} {} {}", new.root.id(), size.0, size.1));
+    }
+    let icon = if !window_ex.icon_data.is_empty() { new.set_icon_data(window_ex.icon_data) }
+               else if let Some(path) = window_ex.icon { new.set_icon(path) }
+               else { Ok(()) };
+    if let Err(err) = icon {
+        warn(&format!("could not set the window icon: {}", err));
+    }
+    rstk::tell_wish(&format!(". configure -padx {} -pady {}", window_ex.margin.0, window_ex.margin.1));
+    if window_ex.font_scale != 1.0 {
+        for font in COMPACT_FONTS {
+            rstk::tell_wish(&font_scale_command(font, window_ex.font_scale));
+        }
+    }
+    if window_ex.kiosk {
+        rstk::tell_wish(KIOSK_PROCS);
+        for command in kiosk_commands(window_ex.kiosk_unlock, window_ex.kiosk_cursor_idle) {
+            rstk::tell_wish(&command);
+        }
+    }
+    new.populate();
+
+    return new;
+}
+
+fn title_command(root: &str, title: &str) -> String {
+    return format!("wm title {} {}", root, tcl_string(title));
+}
+
+fn size_command(root: &str, size: (u64, u64)) -> String {
+    return format!("wm geometry {} {}x{}", root, size.0, size.1);
+}
+
+// Tk writes positions from the right or bottom edge with a '-' in place of
+// the '+'.
+fn location_command(root: &str, location: (i64, i64)) -> String {
+    let offset = |n: i64| if n < 0 { format!("-{}", -n) } else { format!("+{}", n) };
+    return format!("wm geometry {} {}{}", root, offset(location.0), offset(location.1));
+}
+
+// Drawing the user's eye to a window: Tk cannot flash a task bar entry, so
+// the window fades in and out a few times, ending opaque.
+const ATTENTION_PROCS: &str = "proc rsg_attention {w {n 6}} {
+    if {$n <= 0} { catch {wm attributes $w -alpha 1.0} ; return }
+    catch {wm attributes $w -alpha [expr {$n % 2 ? 1.0 : 0.4}]}
+    after 250 [list rsg_attention $w [expr {$n - 1}]]
+}";
+
+// Tcl reporting "restored" when the window is mapped again after being
+// minimized. Bindings on the toplevel also see the events of every element
+// in it, hence the check of %W.
+fn restore_event_commands(root: &str) -> Vec<String> {
+    return vec![
+        format!("bind {} <Unmap> {{+if {{\"%W\" eq \"{}\" && [wm state {}] eq \"iconic\"}} {{ set ::rsg_minimized 1 }}}}", root, root, root),
+        format!("bind {} <Map> {{+if {{\"%W\" eq \"{}\" && [info exists ::rsg_minimized]}} {{ unset ::rsg_minimized ; puts restored ; flush stdout }}}}", root, root)
+    ];
+}
+
+// A window with no widgets built yet.
+fn blank_window(window_name: String, layout: Vec<Vec<RsgObj>>, root: rstk::TkTopLevel,
+                window_ex: RsgWindowEx, events: Arc<handle::EventQueue>) -> Window {
+    return Window{
+        registry: registry::Registry::default(),
+        inputs: Vec::new(),
+        name: window_name,
+        layout,
+        root,
+        sliders: vec![],
+        spins: vec![],
+        editables: vec![],
+        kvs: vec![],
+        kv_entries: HashMap::new(),
+        segments: vec![],
+        radio_groups: vec![],
+        checklists: vec![],
+        checkboxes: vec![],
+        radios: vec![],
+        option_menus: vec![],
+        image_sizes: HashMap::new(),
+        button_images: vec![],
+        icon: None,
+        folder_browses: vec![],
+        color_choosers: vec![],
+        color_chooser_events: HashSet::new(),
+        value_vars: HashMap::new(),
+        element_types: HashMap::new(),
+        element_colors: HashMap::new(),
+        element_metadata: HashMap::new(),
+        checks: HashMap::new(),
+        failing_checks: RefCell::new(HashSet::new()),
+        disabled_blend: 0.5,
+        element_gap: window_ex.element_gap,
+        min_touch_size: window_ex.min_touch_size,
+        respect_option_db: window_ex.respect_option_db,
+        kiosk: window_ex.kiosk,
+        element_pads: HashMap::new(),
+        compact_hidden: HashSet::new(),
+        hidden: HashSet::new(),
+        graphs: HashMap::new(),
+        gauges: HashMap::new(),
+        links: vec![],
+        outputs: HashMap::new(),
+        status_bar: None,
+        menu_entries: HashMap::new(),
+        menu_paths: HashMap::new(),
+        submenus: HashMap::new(),
+        menu_values: vec![],
+        menu_checks: HashSet::new(),
+        menu_groups: HashMap::new(),
+        lists: HashMap::new(),
+        untracked: HashSet::new(),
+        clean_values: HashMap::new(),
+        script: vec![],
+        normal_font_sizes: vec![],
+        hooks: HashMap::new(),
+        validators: HashMap::new(),
+        invalid: RefCell::new(HashSet::new()),
+        validation_summary: None,
+        handle: OnceLock::new(),
+        retired: HashSet::new(),
+        closed: Cell::new(false),
+        cached_values: RefCell::new(vec![]),
+        drop_targets: HashSet::new(),
+        last_drop: RefCell::new(None),
+        disabled: RefCell::new(HashSet::new()),
+        return_key_button: None,
+        focus_target: None,
+        timeouts: Cell::new(0),
+        events
+    };
+}
+
+impl Window {
+    // Builds the widgets of `self.layout` and finishes the script recording
+    // started by the caller.
+    fn populate(&mut self) {
+        let mut layout = self.layout.clone();
+        drop_invalid_options(&mut layout);
+        let root = self.root.clone();
+        let mut keys = assign_keys(&layout).into_iter();
+        let mut layers: Vec<(i32, String)> = vec![];
+
+        build_layout(self, &root, &layout, &mut keys, &mut layers);
+        if let Some(id) = &self.focus_target {
+            rstk::tell_wish(&format!("focus {}", id));
+        }
+        if let Some(button) = &self.return_key_button {
+            for command in return_key_commands(root.id(), button, &self.inputs) {
+                rstk::tell_wish(&command);
+            }
+        }
+
+        // Raise/lower only after every widget exists, so a layer is relative to
+        // all of its siblings and not just the ones created before it.
+        layers.sort_by_key(|l| l.0);
+        for (layer, id) in layers.iter() {
+            if *layer > 0 { rstk::tell_wish(&format!("raise {}", id)); }
+        }
+        for (layer, id) in layers.iter().rev() {
+            if *layer < 0 { rstk::tell_wish(&format!("lower {}", id)); }
+        }
+        self.script = rstk::end_recording();
+        self.mark_clean();
+    }
+}
+
+// Creates the widgets for `layout` in a grid inside `parent`, recursing into
+// containers. `keys` yields the keys from `assign_keys`, in the same order.
+fn build_layout(new: &mut Window, parent: &impl rstk::TkWidget, layout: &Vec<Vec<RsgObj>>,
+                keys: &mut std::vec::IntoIter<String>, layers: &mut Vec<(i32, String)>) {
+    let collisions = span_collisions(layout);
+    let options = options();
+    for i in 0..layout.len() {
+        let mut row_ids: Vec<String> = vec![];
+        for j in 0..layout[i].len() {
+            let x = &layout[i][j];
+            let key = keys.next().unwrap();
+            let pad = element_pad(x.pad, new.element_gap);
+
+            let id: String = match x.r#type {
+                RsgObjType::Text => {
+                    let n = rstk::make_label(parent);
+                    n.text(&x.name);
+
+                    let size = options.element_size(x.size, options.auto_size_text);
+                    if size.0 != 0 { n.width(size.0 as i64); }
+                    if size.1 != 0 { rstk::tell_wish(&format!("after idle [list rsg_text_height {} {}]", n.id(), size.1)); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    for command in color_commands(n.id(), x.color, ("fg", "bg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+                    if let Some(wrap) = x.wrap {
+                        for command in wrap_commands(n.id(), wrap, x.expand.0) {
+                            rstk::tell_wish(&command);
+                        }
+                    }
+
+                    if x.editable {
+                        rstk::tell_wish(&format!("bind {} <Button-1> {{ rsg_edit_label {} }}", n.id(), n.id()));
+                        new.registry.set_event_name(n.id(), &key);
+                        new.editables.push(n.id().to_string());
+                    } else if x.enable_events {
+                        rstk::tell_wish(&format!("bind {} <Button-1> {{ puts clicked-{} ; flush stdout }}", n.id(), n.id()));
+                        new.registry.set_event_name(n.id(), &x.name);
+                    }
+
+                    n.id().to_string()
+                }
+                RsgObjType::Button => {
+                    let n = rstk::make_button(parent);
+                    n.text(&x.name);
+                    if let Some(path) = &x.image {
+                        match load_image(ImageSource::Path(path), (0, 0)) {
+                            Ok(img) => {
+                                rstk::tell_wish(&format!("{} configure -image {} -compound left", n.id(), img));
+                                new.button_images.push(img);
+                            }
+                            Err(err) => warn(&format!("could not load image \"{}\" for button \"{}\": {}", path, x.name, err))
+                        }
+                    }
+
+                    let size = options.element_size(x.size, options.auto_size_buttons);
+                    if size.0 != 0 { n.width(size.0 as i64); }
+                    if size.1 != 0 { n.height(size.1 as i64); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+
+                    for command in color_commands(n.id(), x.color, ("fg", "bg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    n.command(||{});
+                    for command in click_commands(n.id(), x.enable_events) {
+                        rstk::tell_wish(&command);
+                    }
+                    new.registry.set_event_name(n.id(), &x.name);
+
+                    n.id().to_string()
+                }
+                RsgObjType::CheckBox => {
+                    let n = rstk::make_check_button(parent);
+                    n.text(&x.name);
+
+                    if x.size.0 != 0 { n.width(x.size.0 as i64); }
+                    if x.size.1 != 0 { n.height(x.size.1 as i64); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    for command in color_commands(n.id(), x.color, ("fg", "bg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    if x.tristate {
+                        rstk::tell_wish(&format!("{} configure -tristatevalue mixed", n.id()));
+                    }
+
+                    n.command(|_|{});
+                    new.registry.set_event_name(n.id(), &x.name);
+                    new.checkboxes.push(n.id().to_string());
+
+                    n.id().to_string()
+                }
+                RsgObjType::Radio => {
+                    let group = radio_group_name(parent.id(), i, x.group.as_deref());
+                    let n = rstk::make_radio_button(parent, &group, &x.name);
+                    n.text(&x.name);
+
+                    // ttk radios have no height
+                    if x.size.0 != 0 { n.width(x.size.0 as i64); };
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    for command in color_commands(n.id(), x.color, ("fg", "bg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    n.command(|_|{});
+                    new.registry.set_event_name(n.id(), &x.name);
+                    new.radios.push(n.id().to_string());
+
+                    n.id().to_string()
+                }
+                RsgObjType::Input => {
+                    let n = rstk::make_text(parent);
+                    n.insert((0, 0), &x.name);
+
+                    let new_name = x.name.clone();
+                    if new_name != "".to_string() { n.insert((0, 0), &new_name); }
+
+                    if x.size.0 == 0 { n.width(10); }
+                    else { n.width(x.size.0 as u64); }
+                    if x.size.1 == 0 { n.height(1); }
+                    else { n.height(x.size.1 as u64); }
+
+                    new.inputs.push(n.id().to_string());
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    for command in color_commands(n.id(), x.color, ("bg", "fg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    if let Some(limit) = x.char_limit {
+                        for command in char_limit_commands(n.id(), limit) {
+                            rstk::tell_wish(&command);
+                        }
+                    }
+                    if x.enable_events {
+                        rstk::tell_wish(&input_events_command(n.id()));
+                    }
+
+                    n.id().to_string()
+                }
+                RsgObjType::NumberInput => {
+                    let id = rstk::next_wid(parent.id());
+                    for command in number_input_commands(&id, if x.size.0 == 0 { 10 } else { x.size.0 }, x.range, decimal_separator()) {
+                        rstk::tell_wish(&command);
+                    }
+                    apply_colors(&id, x.color);
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {}", id, i, j, pad.0, pad.1));
+
+                    // listed with the inputs, so read() gives the values in
+                    // the same order as if it were one
+                    new.inputs.push(id.clone());
+                    id
+                }
+                RsgObjType::Slider => {
+                    let id = rstk::next_wid(parent.id());
+                    for command in slider_commands(&id, x) {
+                        rstk::tell_wish(&command);
+                    }
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {}", id, i, j, pad.0, pad.1));
+
+                    for command in color_commands(&id, x.color, ("bg", "fg"), new.respect_option_db) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    // reported through rstk's scale_value proc
+                    if x.enable_events {
+                        rstk::tell_wish(&format!("{} configure -command [list scale_value {}]", id, id));
+                    }
+
+                    new.sliders.push(id.clone());
+                    id
+                }
+                RsgObjType::Separator => {
+                    let vertical = matches!(RsgOrientation::to_enum(&x.name), RsgOrientation::Vertical);
+                    let custom = x.size != (0, 0) || !matches!(x.color, (RsgColor::None, RsgColor::None));
+                    let id = rstk::next_wid(parent.id());
+                    if custom {
+                        for command in styled_separator_commands(&id, vertical, x.size, x.color) {
+                            rstk::tell_wish(&command);
+                        }
+                    } else {
+                        rstk::tell_wish(&format!("ttk::separator {} -orient {}", id, x.name));
+                    }
+
+                    // a fixed length keeps the line from stretching
+                    let length = x.size.0;
+                    let sticky = if length != 0 { "" } else if vertical { "ns" } else { "ew" };
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {} -sticky {{{}}}", id, i, j, pad.0, pad.1, sticky));
+
+                    id
+                }
+                RsgObjType::Spin => {
+                    let n = rstk::make_spinbox_range(parent, x.range.0 as f64, x.range.1 as f64, x.step);
+                    rstk::tell_wish(&format!("{} set {}", n.id(), x.range.0));
+
+                    if x.size.0 != 0 { rstk::tell_wish(&format!("{} configure -width {}", n.id(), x.size.0)); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    apply_colors(n.id(), x.color);
+
+                    if x.enable_events {
+                        rstk::tell_wish(&format!(
+                            "{} configure -command {{ puts cb1s-{}-[{} get] ; flush stdout }}",
+                            n.id(), n.id(), n.id()
+                        ));
+                        new.registry.set_event_name(n.id(), &x.name);
+                    }
+
+                    new.spins.push(n.id().to_string());
+                    n.id().to_string()
+                }
+                RsgObjType::Progress => {
+                    let rsg_orientation = RsgOrientation::to_enum(&x.name);
+                    let rstk_orientation: rstk::Orientation;
+                    if let RsgOrientation::Horizontal = rsg_orientation {
+                        rstk_orientation = rstk::Orientation::Horizontal;
+                    } else { rstk_orientation = rstk::Orientation::Vertical; }
+
+                    let n = rstk::make_progressbar(parent, rstk_orientation, rstk::ProgressMode::Determinate);
+                    n.maximum(x.range.1 as f64);
+
+                    if x.size.0 != 0 { n.length(x.size.0); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    n.id().to_string()
+                }
+                RsgObjType::Frame => {
+                    let n = rstk::make_label_frame(parent);
+                    n.text(&x.name);
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    // the frame's key was taken above, so its children follow it
+                    build_layout(new, &n, &x.layout, keys, layers);
+
+                    n.id().to_string()
+                }
+                RsgObjType::Paned => {
+                    let id = rstk::next_wid(parent.id());
+                    rstk::tell_wish(&format!("ttk::panedwindow {} -orient {}", id, x.name));
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {} -sticky nsew", id, i, j, pad.0, pad.1));
+
+                    // the sides are built as columns gridded in the
+                    // panedwindow, which takes them over when they are added
+                    build_layout(new, &rstk::TkFrame{ id: id.clone() }, &x.layout, keys, layers);
+                    for command in paned_commands(&id, x.size.0) {
+                        rstk::tell_wish(&command);
+                    }
+                    id
+                }
+                RsgObjType::Column => {
+                    let n = rstk::make_frame(parent);
+
+                    // columns sit side by side in one row, so keep each
+                    // stack at the top of the row rather than centred
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .sticky(rstk::Sticky::N)
+                    .layout();
+
+                    build_layout(new, &n, &x.layout, keys, layers);
+
+                    n.id().to_string()
+                }
+                RsgObjType::Canvas => {
+                    let n = rstk::make_canvas(parent);
+                    if x.size.0 != 0 { n.width(x.size.0); }
+                    if x.size.1 != 0 { n.height(x.size.1); }
+                    if let RsgColor::None = x.color.1 {} else {
+                        rstk::tell_wish(&format!("{} configure -background {{{}}}", n.id(), get_rsg_color(x.color.1)));
+                    }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    if x.enable_events {
+                        rstk::tell_wish(&format!("bind {} <Button-1> {{ puts cb1s-{}-%x,%y ; flush stdout }}", n.id(), n.id()));
+                    }
+
+                    n.id().to_string()
+                }
+                RsgObjType::Gauge => {
+                    let n = rstk::make_canvas(parent);
+                    if let RsgColor::None = x.color.1 {} else {
+                        rstk::tell_wish(&format!("{} configure -background {{{}}}", n.id(), get_rsg_color(x.color.1)));
+                    }
+                    let color = if let RsgColor::None = x.color.0 { String::from("black") } else { get_rsg_color(x.color.0) };
+                    let gauge = Gauge{ range: x.range, width: if x.size.0 == 0 { 160 } else { x.size.0 } };
+                    for command in gauge_draw_commands(n.id(), gauge, &color) {
+                        rstk::tell_wish(&command);
+                    }
+                    rstk::tell_wish(&gauge_command(n.id(), gauge, x.range.0 as f64));
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.gauges.insert(n.id().to_string(), gauge);
+                    n.id().to_string()
+                }
+                RsgObjType::Graph => {
+                    let n = rstk::make_canvas(parent);
+                    if x.size.0 != 0 { n.width(x.size.0); }
+                    if x.size.1 != 0 { n.height(x.size.1); }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    let x_axis = graph_axis(x.range);
+                    let y_axis = graph_axis(x.y_range);
+                    let plot = rstk::make_x_y(&n, x_axis, y_axis).plot();
+                    new.graphs.insert(n.id().to_string(), Graph{ plot, x_axis, y_axis, colors: vec![] });
+
+                    n.id().to_string()
+                }
+                RsgObjType::Output => {
+                    let n = rstk::make_text(parent);
+                    n.width(if x.size.0 == 0 { 40 } else { x.size.0 });
+                    n.height(if x.size.1 == 0 { 10 } else { x.size.1 });
+                    rstk::tell_wish(&format!("{} configure -state disabled", n.id()));
+                    apply_colors(n.id(), x.color);
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.outputs.insert(n.id().to_string(), x.max_lines);
+                    *LAST_OUTPUT.lock().unwrap() = Some((n.id().to_string(), x.max_lines));
+                    n.id().to_string()
+                }
+                RsgObjType::ScrolledText => {
+                    let frame = rstk::make_frame(parent);
+                    let n = rstk::make_text(&frame);
+                    n.width(if x.size.0 == 0 { 40 } else { x.size.0 });
+                    n.height(if x.size.1 == 0 { 10 } else { x.size.1 });
+                    rstk::tell_wish(&format!("{} configure -state disabled", n.id()));
+                    apply_colors(n.id(), x.color);
+                    let scrollbar = rstk::make_vertical_scrollbar(&frame, &n);
+
+                    n.grid().row(0).column(0).sticky(rstk::Sticky::NESW).layout();
+                    scrollbar.grid().row(0).column(1).sticky(rstk::Sticky::NS).layout();
+                    frame.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    n.id().to_string()
+                }
+                RsgObjType::SizeGrip => {
+                    // A grid cell cannot reach the corner when the layout
+                    // is ragged or a container holds the grip, so it is
+                    // placed on the toplevel instead and raised above
+                    // everything built after it.
+                    let id = rstk::next_wid(".");
+                    rstk::tell_wish(&format!("ttk::sizegrip {} ; place {} -relx 1.0 -rely 1.0 -anchor se", id, id));
+                    layers.push((i32::MAX, id.clone()));
+                    id
+                }
+                RsgObjType::StatusBar => {
+                    let n = rstk::make_label(parent);
+                    n.text(&x.name);
+                    n.relief(rstk::Relief::Sunken);
+                    apply_colors(n.id(), x.color);
+
+                    let widest = layout.iter().map(|row| row.len()).max().unwrap_or(1);
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .column_span((widest - j) as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .sticky(rstk::Sticky::EW)
+                    .layout();
+
+                    new.status_bar.get_or_insert(n.id().to_string());
+                    n.id().to_string()
+                }
+                RsgObjType::TitleBar => {
+                    let n = rstk::make_frame(parent);
+                    let title = rstk::make_label(&n);
+                    title.text(&x.name);
+                    apply_colors(title.id(), x.color);
+                    let minimize = rstk::next_wid(n.id());
+                    let close = rstk::next_wid(n.id());
+                    for command in titlebar_commands(n.id(), title.id(), &minimize, &close) {
+                        rstk::tell_wish(&command);
+                    }
+
+                    let widest = layout.iter().map(|row| row.len()).max().unwrap_or(1);
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .column_span((widest - j) as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .sticky(rstk::Sticky::EW)
+                    .layout();
+
+                    title.id().to_string()
+                }
+                RsgObjType::Segmented => {
+                    let n = rstk::make_frame(parent);
+                    let var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {}", var, tcl_string(&x.value)));
+
+                    // Toolbutton draws the chosen radio pressed in every
+                    // theme; custom colors only override the chosen state
+                    let mut style = String::from("Toolbutton");
+                    if !matches!(x.color, (RsgColor::None, RsgColor::None)) {
+                        style = format!("Rsg{}.Toolbutton", n.id().replace('.', "_"));
+                        if let RsgColor::None = x.color.0 {} else {
+                            rstk::tell_wish(&format!("ttk::style map {} -foreground [list selected {{{}}}]", style, get_rsg_color(x.color.0)));
+                        }
+                        if let RsgColor::None = x.color.1 {} else {
+                            rstk::tell_wish(&format!("ttk::style map {} -background [list selected {{{}}}]", style, get_rsg_color(x.color.1)));
+                        }
+                    }
+
+                    for (k, option) in x.options.iter().enumerate() {
+                        let segment = rstk::next_wid(n.id());
+                        rstk::tell_wish(&format!(
+                            "ttk::radiobutton {} -text {} -value {} -variable {} -style {} -command {{ puts cb1s-{}-[set {}] ; flush stdout }}",
+                            segment, tcl_string(option), tcl_string(option), var, style, n.id(), var
+                        ));
+                        if x.size.0 != 0 { rstk::tell_wish(&format!("{} configure -width {}", segment, x.size.0)); }
+                        rstk::tell_wish(&format!("grid {} -row 0 -column {} -sticky ew", segment, k));
+                    }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.segments.push(n.id().to_string());
+                    new.value_vars.insert(n.id().to_string(), var);
+                    n.id().to_string()
+                }
+                RsgObjType::Checklist => {
+                    let id = rstk::next_wid(parent.id());
+                    let var = rstk::next_var();
+                    for command in checklist_commands(&id, &var, &x.options, if x.size.1 == 0 { 150 } else { x.size.1 }) {
+                        rstk::tell_wish(&command);
+                    }
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {}", id, i, j, pad.0, pad.1));
+
+                    new.checklists.push(id.clone());
+                    new.value_vars.insert(id.clone(), var);
+                    id
+                }
+                RsgObjType::RadioGroup => {
+                    let n = rstk::make_frame(parent);
+                    let var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {}", var, tcl_string(&x.value)));
+                    // ttk radios take their colors from a style
+                    let style = format!("Rsg{}.TRadiobutton", n.id().replace('.', "_"));
+                    if let RsgColor::None = x.color.0 {} else {
+                        rstk::tell_wish(&format!("ttk::style configure {} -foreground {{{}}}", style, get_rsg_color(x.color.0)));
+                    }
+                    if let RsgColor::None = x.color.1 {} else {
+                        rstk::tell_wish(&format!("ttk::style configure {} -background {{{}}}", style, get_rsg_color(x.color.1)));
+                    }
+                    for (k, option) in x.options.iter().enumerate() {
+                        let radio = rstk::next_wid(n.id());
+                        rstk::tell_wish(&radio_group_command(n.id(), &radio, &var, option, k));
+                        if !matches!(x.color, (RsgColor::None, RsgColor::None)) {
+                            rstk::tell_wish(&format!("{} configure -style {}", radio, style));
+                        }
+                    }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.radio_groups.push(n.id().to_string());
+                    new.value_vars.insert(n.id().to_string(), var);
+                    n.id().to_string()
+                }
+                RsgObjType::OptionMenu => {
+                    let id = rstk::next_wid(parent.id());
+                    let var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {}", var, tcl_string(&x.value)));
+                    rstk::tell_wish(&option_menu_command(&id, &var, &x.options));
+                    if x.size.0 != 0 { rstk::tell_wish(&format!("{} configure -width {}", id, x.size.0)); }
+                    apply_colors(&id, x.color);
+
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {}", id, i, j, pad.0, pad.1));
+
+                    new.option_menus.push(id.clone());
+                    new.value_vars.insert(id.clone(), var);
+                    id
+                }
+                RsgObjType::ButtonMenu => {
+                    let id = rstk::next_wid(parent.id());
+                    let menu = rstk::next_wid(&id);
+                    for command in button_menu_commands(&id, &menu, &x.name, &x.options) {
+                        rstk::tell_wish(&command);
+                    }
+                    if x.size.0 != 0 { rstk::tell_wish(&format!("{} configure -width {}", id, x.size.0)); }
+                    apply_colors(&id, x.color);
+
+                    rstk::tell_wish(&format!("grid {} -row {} -column {} -padx {} -pady {}", id, i, j, pad.0, pad.1));
+
+                    new.registry.set_event_name(&id, &x.name);
+                    id
+                }
+                RsgObjType::FolderBrowse => {
+                    let n = rstk::make_button(parent);
+                    n.text(&x.name);
+                    if x.size.0 != 0 { n.width(x.size.0 as i64); }
+                    apply_colors(n.id(), x.color);
+
+                    let var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {{}}", var));
+                    let target = (0..j).rev()
+                        .find(|&k| matches!(layout[i][k].r#type, RsgObjType::Input))
+                        .map(|k| row_ids[k].as_str());
+                    let command = folder_browse_command(n.id(), &var, &x.value, target, x.enable_events);
+                    rstk::tell_wish(&format!("{} configure -command {{ {} }}", n.id(), command));
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.folder_browses.push(n.id().to_string());
+                    new.value_vars.insert(n.id().to_string(), var);
+                    n.id().to_string()
+                }
+                RsgObjType::ColorChooser => {
+                    let n = rstk::make_button(parent);
+                    n.text(&x.name);
+                    if x.size.0 != 0 { n.width(x.size.0 as i64); }
+                    apply_colors(n.id(), x.color);
+
+                    let var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {}", var, tcl_string(&x.value)));
+                    let command = color_chooser_command(n.id(), &var, None, x.enable_events);
+                    rstk::tell_wish(&format!("{} configure -command {{ {} }}", n.id(), command));
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    new.color_choosers.push(n.id().to_string());
+                    if x.enable_events { new.color_chooser_events.insert(n.id().to_string()); }
+                    new.value_vars.insert(n.id().to_string(), var);
+                    n.id().to_string()
+                }
+                RsgObjType::Kv => {
+                    let n = rstk::make_frame(parent);
+
+                    let label = rstk::make_label(&n);
+                    label.text(&format!("{}:", x.name));
+                    if x.size.0 != 0 { label.width(x.size.0 as i64); }
+                    label.grid().row(0).column(0).sticky(rstk::Sticky::W).layout();
+
+                    let value = rstk::make_entry(&n);
+                    if x.size.1 != 0 { value.width(x.size.1); }
+                    rstk::tell_wish(&readonly_entry_command(value.id(), &x.value));
+                    value.grid().row(0).column(1).padx(4).layout();
+
+                    let copy = rstk::make_button(&n);
+                    copy.text("copy");
+                    rstk::tell_wish(&format!("{} configure -command {{ clipboard clear ; clipboard append [{} get] }}",
+                                             copy.id(), value.id()));
+                    copy.grid().row(0).column(2).layout();
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .sticky(rstk::Sticky::W)
+                    .layout();
+
+                    new.kvs.push(n.id().to_string());
+                    new.kv_entries.insert(n.id().to_string(), value.id().to_string());
+                    n.id().to_string()
+                }
+                RsgObjType::Image => {
+                    let n = rstk::make_label(parent);
+
+                    match load_image(ImageSource::of(x), x.size) {
+                        Ok(img) => rstk::tell_wish(&format!("{} configure -image {}", n.id(), img)),
+                        Err(err) => {
+                            let shown = if x.data.is_empty() { x.name.clone() } else { String::from("image data") };
+                            warn(&format!("could not load image \"{}\": {}", shown, err));
+                            n.text(&format!("[{}]", shown));
+                        }
+                    }
+
+                    n.grid()
+                    .row(i as u64).column(j as u64)
+                    .padx(pad.0).pady(pad.1)
+                    .layout();
+
+                    rstk::tell_wish(&format!("bind {} <Button-1> {{ puts clicked-{} ; flush stdout }}", n.id(), n.id()));
+                    if x.data.is_empty() { new.registry.set_event_name(n.id(), &x.name); }
+                    new.image_sizes.insert(n.id().to_string(), x.size);
+
+                    n.id().to_string()
+                }
+            };
+
+            if !x.track_changes { new.untracked.insert(key.clone()); }
+            new.registry.insert(key.clone(), id.clone());
+            if x.key.is_some() { new.registry.report_by_key(&id); }
+            if let Some(font) = x.font { rstk::tell_wish(&font_command(&id, font)); }
+            if let Some(border) = x.border { rstk::tell_wish(&border_command(&id, border)); }
+            if let Some(command) = hover_command(&id, x.hover_color) { rstk::tell_wish(&command); }
+            if let Some(cursor) = &x.cursor { rstk::tell_wish(&format!("{} configure -cursor {}", id, cursor)); }
+            if let Some(tooltip) = &x.tooltip {
+                for command in tooltip_commands(&id, tooltip) {
+                    rstk::tell_wish(&command);
+                }
+            }
+            new.element_types.insert(id.clone(), x.r#type.clone());
+            new.element_colors.insert(id.clone(), x.color);
+            if let Some(metadata) = &x.metadata { new.element_metadata.insert(id.clone(), metadata.clone()); }
+            if let Some(check) = &x.check { new.checks.insert(id.clone(), check.clone()); }
+            new.element_pads.insert(id.clone(), pad);
+            if x.hide_in_compact { new.compact_hidden.insert(id.clone()); }
+            let touch_size = x.min_touch_size.or(if is_touch_target(&x.r#type) { new.min_touch_size } else { None });
+            if let Some(size) = touch_size { rstk::tell_wish(&min_size_command(&id, size)); }
+            if let Some(command) = default_command(&id, &x.r#type, &x.value) { rstk::tell_wish(&command); }
+            for command in justify_commands(&id, &x.r#type, x.justify) {
+                rstk::tell_wish(&command);
+            }
+            for command in expand_commands(parent.id(), &id, (i, j), grows(x)) {
+                rstk::tell_wish(&command);
+            }
+            if x.disabled { let _ = new.set_disabled(&key, true); }
+            if x.focus { new.focus_target = Some(id.clone()); }
+            if x.bind_return_key && matches!(x.r#type, RsgObjType::Button) {
+                if let Some(previous) = new.return_key_button.replace(id.clone()) {
+                    if cfg!(debug_assertions) {
+                        warn(&format!("bind_return_key set on both {} and {}, Enter clicks {}",
+                                      new.registry.key(&previous).unwrap_or(&previous), key, key));
+                    }
+                }
+            }
+            if let Some(command) = span_command(&id, x.span) {
+                rstk::tell_wish(&command);
+            }
+            if collisions.contains(&(i, j)) {
+                rstk::tell_wish(&format!("after idle {{ grid remove {} }}", id));
+            }
+            if !x.visible {
+                // after the minimum size above, which grids the element again
+                rstk::tell_wish(&format!("after idle {{ grid remove {} }}", id));
+                new.hidden.insert(id.clone());
+            }
+
+            row_ids.push(id.clone());
+            if x.layer != 0 { layers.push((x.layer, id)); }
+        }
+    }
+}
+
+impl Window {
+    pub fn read(&self) -> (String, Vec<String>) {
+        let ev = self.read_event(None);
+        let ret_values: Vec<String> = self.values().into_iter().map(|(_, value)| value).collect();
+        return (ev, ret_values);
+    }
+
+    /// Same as [`Window::read`], with the values keyed by element key
+    /// rather than listed by position.
+    pub fn read_map(&self) -> (String, HashMap<String, String>) {
+        let ev = self.read_event(None);
+        return (ev, self.values().into_iter().collect());
+    }
+
+    /// Same as [`Window::read`], with the values in a [`Values`] to look up
+    /// by element key.
+    pub fn read_values(&self) -> (String, Values) {
+        let ev = self.read_event(None);
+        let values = self.values().into_iter().map(|(key, value)| (key, RsgValue::from(value))).collect();
+        return (ev, Values::new(values));
+    }
+
+    /// Same as [`Window::read`], but gives up after `ms` milliseconds
+    /// without an event and returns [`TIMEOUT_KEY`] with the current values,
+    /// e.g. to poll a device between events.
+    pub fn read_timeout(&self, ms: u64) -> (String, Vec<String>) {
+        let ev = self.read_event(Some(ms));
+        let ret_values: Vec<String> = self.values().into_iter().map(|(_, value)| value).collect();
+        return (ev, ret_values);
+    }
+
+    /// Returns the next event and the values if an event is waiting, and
+    /// `None` right away otherwise, e.g. to handle events from a render
+    /// loop. A closed window gives [`WIN_CLOSED`] as `read()` does.
+    ///
+    /// Only events wish has already reported count, so polling does not
+    /// wait for wish, and works inside [`Window::freeze`] too. Reading the
+    /// values of an event still asks wish for them.
+    pub fn poll(&self) -> Option<(String, Vec<String>)> {
+        let ev = self.read_event(Some(0));
+        if ev == TIMEOUT_KEY {
+            return None;
+        }
+        let ret_values: Vec<String> = self.values().into_iter().map(|(_, value)| value).collect();
+        return Some((ev, ret_values));
+    }
+
+    // Waits for the next event from wish, or until `timeout_ms` have passed,
+    // runs the hooks registered for it and returns it in the form `read()`
+    // reports it. Events wish printed before the timer fired come first. A
+    // timeout of 0 does not wait at all, and only looks at the events read
+    // from wish so far.
+    fn read_event(&self, timeout_ms: Option<u64>) -> String {
+        if self.was_closed() {
+            self.closed.set(true);
+            return WIN_CLOSED.to_string();
+        }
+        let polling = timeout_ms == Some(0);
+        let timeout = timeout_ms.filter(|_| !polling).map(|ms| {
+            let n = self.timeouts.get() + 1;
+            self.timeouts.set(n);
+            rstk::tell_wish(&format!("set ::rsg_timeout [after {} {{ puts timeout-{} ; flush stdout }}]", ms, n));
+            format!("timeout-{}", n)
+        });
+        // inside freeze() the timer, and whatever the event depends on, would
+        // otherwise wait for the release
+        rstk::flush_held();
+        let mut event: String;
+        loop {
+            // events queued before the close are still delivered, in order
+            event = if polling {
+                match rstk::pending_event() {
+                    Some(event) => event,
+                    None if rstk::wish_gone() => String::from(WIN_CLOSED),
+                    None => return TIMEOUT_KEY.to_string()
+                }
+            } else {
+                rstk::mainloop().unwrap_or(String::from(WIN_CLOSED))
+            };
+            if event == WIN_CLOSED {
+                self.closed.set(true);
+                return event;
+            }
+            if event.starts_with("timeout-") {
+                if Some(&event) == timeout.as_ref() {
+                    return TIMEOUT_KEY.to_string();
+                }
+                continue;
+            }
+            if event == "injected" {
+                // the event may have been dropped as stale since the wake-up
+                match self.events.pop() {
+                    Some(id) => event = id,
+                    None => continue
+                }
+            } else if event == "restored" {
+                return RESTORED_KEY.to_string();
+            } else if event == "close-request" {
+                if !self.is_dirty() || self.confirm_discard() {
+                    // closes through the usual path, reporting "Quit"
+                    rstk::tell_wish("puts exit ; flush stdout");
+                }
+                continue;
+            }
+
+            // events from widgets replaced by swap_layout may still be on
+            // their way
+            if self.retired.contains(split_event(&event).0) {
+                continue;
+            }
+            // wish may still report a click that raced the disable
+            if self.disabled.borrow().contains(split_event(&event).0) {
+                continue;
+            }
+            break;
+        }
+        if timeout.is_some() {
+            rstk::tell_wish("after cancel $::rsg_timeout");
+        }
+
+        let or = String::from("None");
+        let (id, value, mouse_button, modifiers) = split_event(&event);
+        let ev = match value {
+            Some(value) => self.registry.event_name(id).unwrap_or(&or).to_owned() + ":::" + value,
+            None => self.registry.event_name(id).unwrap_or(&or).to_string()
+        };
+
+        *self.last_drop.borrow_mut() = None;
+        if value == Some("dropped") && self.drop_targets.contains(id) {
+            let source = rstk::ask_wish("puts $::rsg_drop_source ; flush stdout");
+            let text = rstk::ask_wish("puts $::rsg_drop_text ; flush stdout");
+            let source_key = self.registry.key(&source).unwrap_or(&source).to_string();
+            *self.last_drop.borrow_mut() = Some((source_key, text));
+        }
+        let value = value.map(|value| value.to_string());
+
+        let key = self.registry.key(id).unwrap_or(&ev).to_string();
+        self.apply_links(&key);
+        self.recheck_invalid();
+        let metadata = self.element_metadata.get(id).cloned();
+        self.run_hooks(&RsgEvent{ key, event: ev.clone(), value, mouse_button, modifiers, metadata });
+        return ev;
+    }
+
+    /// Keeps `target_key` showing the value of `source_key`, optionally
+    /// passed through `transform` first. Links are applied inside `read()`
+    /// before the event is returned.
+    ///
+    /// A checkbox source gives "true" or "false": linked to another checkbox
+    /// it sets it, linked to any other element it enables it while checked
+    /// and disables it otherwise. Other targets get the value itself.
+    ///
+    /// Only links reachable from the element which reported the event are
+    /// applied, so a source without events updates its targets once another
+    /// event passes through it. Changes made by links do not trigger further
+    /// events, and no element is set twice for one event, so two-way links
+    /// and other cycles are harmless.
+    pub fn link(&mut self, source_key: &str, target_key: &str, transform: Option<fn(&str) -> String>) -> Result<(), RsgError> {
+        let source = &self.widget_id(source_key)?.to_string();
+        let target = &self.widget_id(target_key)?.to_string();
+        if self.link_query(source).is_none() {
+            return Err(RsgError::WrongElementType(source_key.to_string()));
+        }
+        let toggles = matches!(self.element_types.get(source), Some(RsgObjType::CheckBox));
+        if !toggles && self.set_value_command(target, "").is_none() {
+            return Err(RsgError::WrongElementType(target_key.to_string()));
+        }
+
+        self.links.push(Link{ source: source_key.to_string(), target: target_key.to_string(), transform });
+        return Ok(());
+    }
+
+    fn apply_links(&self, changed: &str) {
+        let pairs: Vec<(&str, &str)> = self.links.iter().map(|l| (l.source.as_str(), l.target.as_str())).collect();
+        for n in link_order(&pairs, changed) {
+            let link = &self.links[n];
+            let source = &self.registry.id(&link.source).unwrap().to_string();
+            let target = &self.registry.id(&link.target).unwrap().to_string();
+
+            let query = self.link_query(source).unwrap();
+            let mut value = self.read_value(source, &query);
+            if let Some(transform) = link.transform {
+                value = transform(&value);
+            }
+
+            let toggles = matches!(self.element_types.get(source), Some(RsgObjType::CheckBox))
+                       && !matches!(self.element_types.get(target), Some(RsgObjType::CheckBox));
+            if toggles {
+                let _ = self.set_disabled(&link.target, value != "true");
+            } else if let Some(command) = self.set_value_command(target, &value) {
+                rstk::tell_wish(&command);
+            }
+        }
+    }
+
+    // Tcl returning what a link from the element carries.
+    fn link_query(&self, id: &String) -> Option<String> {
+        match self.element_types.get(id) {
+            Some(RsgObjType::CheckBox) => return Some(format!("expr {{[set [{} cget -variable]] ? \"true\" : \"false\"}}", id)),
+            Some(RsgObjType::Text) => return Some(format!("{} cget -text", id)),
+            _ => return self.value_query(id)
+        }
+    }
+
+    // Tcl making the element show `value`, if it shows a value at all.
+    // Checkable menu entries and menu radio groups have no element type and
+    // are set through their variables.
+    fn set_value_command(&self, id: &String, value: &str) -> Option<String> {
+        let quoted = tcl_string(value);
+        match self.element_types.get(id) {
+            Some(RsgObjType::Text) => return Some(format!("{} configure -text {}", id, quoted)),
+            Some(RsgObjType::Input) => return Some(format!("{} delete 1.0 end ; {} insert end {}", id, id, quoted)),
+            Some(RsgObjType::NumberInput) => return Some(format!("{} delete 0 end ; {} insert 0 {}", id, id, quoted)),
+            Some(RsgObjType::Slider) | Some(RsgObjType::Spin) => return Some(format!("{} set {}", id, quoted)),
+            Some(RsgObjType::Progress) => return Some(format!("{} configure -value {}", id, quoted)),
+            Some(RsgObjType::CheckBox) => return Some(format!("set [{} cget -variable] {}", id, if value == "true" { 1 } else { 0 })),
+            Some(RsgObjType::Kv) => return Some(readonly_entry_command(&self.kv_entries[id], value)),
+            Some(RsgObjType::Segmented) | Some(RsgObjType::RadioGroup) | Some(RsgObjType::OptionMenu) => {
+                return Some(format!("set {} {}", self.value_vars[id], quoted));
+            }
+            Some(_) => return None,
+            None => return menu_value_command(self.menu_checks.contains(id), self.value_vars.get(id), value)
+        }
+    }
+
+    fn run_hooks(&self, event: &RsgEvent) {
+        for key in [event.key.as_str(), "*"] {
+            if let Some(hooks) = self.hooks.get(key) {
+                for hook in hooks {
+                    hook(event);
+                }
+            }
+        }
+    }
+
+    /// Registers a hook run inside `read()` for every event from the element
+    /// with the given key, or for every event at all with the key "*".
+    ///
+    /// Hooks only observe: the event is still returned from `read()`
+    /// afterwards. Several hooks may be registered for one key and run in
+    /// registration order, before the "*" hooks.
+    pub fn on_event(&mut self, key: &str, hook: impl Fn(&RsgEvent) + Send + 'static) {
+        self.hooks.entry(key.to_string()).or_insert(vec![]).push(Box::new(hook));
+    }
+
+    /// Registers a check of the value of the element with the given key,
+    /// run by [`Window::validate_all`], returning the message to show when
+    /// the value is invalid.
+    pub fn add_validator(&mut self, key: &str, validator: impl Fn(&str) -> Result<(), String> + Send + 'static) {
+        self.validators.entry(key.to_string()).or_insert(vec![]).push(Box::new(validator));
+    }
+
+    /// Makes [`Window::validate_all`] list its messages, one per line, in
+    /// the element with the given key: a text, status bar, output or
+    /// scrolled text.
+    pub fn set_validation_summary(&mut self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        match self.element_types.get(id) {
+            Some(RsgObjType::Text) | Some(RsgObjType::StatusBar) | Some(RsgObjType::Output) | Some(RsgObjType::ScrolledText) => {}
+            _ => return Err(RsgError::WrongElementType(key.to_string()))
+        }
+        self.validation_summary = Some(id.to_string());
+        return Ok(());
+    }
+
+    /// Runs every validator against the current values and returns the
+    /// (key, message) of each failure, in layout order. Invalid elements are
+    /// marked and the first one gets the focus; each mark is cleared by the
+    /// first `read()` after the value is fixed.
+    pub fn validate_all(&self) -> Vec<(String, String)> {
+        let mut failures: Vec<(String, String)> = vec![];
+        for key in self.registry.keys() {
+            if let Some(message) = self.validation_error(key) {
+                failures.push((key.clone(), message));
+            }
+        }
+
+        let mut invalid = self.invalid.borrow_mut();
+        for key in self.validators.keys() {
+            let now_invalid = failures.iter().any(|(k, _)| k == key);
+            if now_invalid != invalid.contains(key) {
+                let id = self.registry.id(key).unwrap();
+                rstk::tell_wish(&invalid_mark_command(id, now_invalid));
+                if now_invalid { invalid.insert(key.clone()); } else { invalid.remove(key); }
+            }
+        }
+
+        if let Some(summary) = &self.validation_summary {
+            let messages: Vec<&str> = failures.iter().map(|(_, message)| message.as_str()).collect();
+            rstk::tell_wish(&summary_command(summary, &self.element_types[summary], &messages.join("\n")));
+        }
+        if let Some((key, _)) = failures.first() {
+            rstk::tell_wish(&format!("focus {}", self.registry.id(key).unwrap()));
+        }
+        return failures;
+    }
+
+    // The message of the first validator of the element failing on its
+    // current value, if any.
+    fn validation_error(&self, key: &str) -> Option<String> {
+        let validators = self.validators.get(key)?;
+        let id = self.registry.id(key)?.to_string();
+        let value = match self.value_query(&id) {
+            Some(query) => self.read_value(&id, &query),
+            None => String::new()
+        };
+        return validators.iter().find_map(|validator| validator(&value).err());
+    }
+
+    // Clears the marks of elements whose values have been fixed.
+    fn recheck_invalid(&self) {
+        let marked: Vec<String> = self.invalid.borrow().iter().cloned().collect();
+        for key in marked {
+            if self.validation_error(&key).is_none() {
+                rstk::tell_wish(&invalid_mark_command(self.registry.id(&key).unwrap(), false));
+                self.invalid.borrow_mut().remove(&key);
+            }
+        }
+    }
+
+    // Runs the check of an input_validated element on its value, marking
+    // the input when it starts failing and clearing the mark once it passes.
+    fn run_check(&self, id: &String, check: &(dyn Fn(&str) -> bool + Send + Sync), value: &str) -> bool {
+        // inputs read back with Tk's trailing newline
+        let valid = check(value.strip_suffix('\n').unwrap_or(value));
+        let mut failing = self.failing_checks.borrow_mut();
+        if valid == failing.contains(id) {
+            rstk::tell_wish(&invalid_mark_command(id, !valid));
+            if valid { failing.remove(id); } else { failing.insert(id.clone()); }
+        }
+        return valid;
+    }
+
+    // Current (key, value) of every value-bearing element, in the order
+    // `read()` lists them.
+    fn values(&self) -> Vec<(String, String)> {
+        if self.closed.get() {
+            return self.cached_values.borrow().clone();
+        }
+        let mut ret_values: Vec<(String, String)> = Vec::new();
+
+        for list in [&self.inputs, &self.sliders, &self.spins, &self.editables, &self.kvs, &self.segments, &self.radio_groups, &self.checklists, &self.folder_browses, &self.color_choosers, &self.option_menus, &self.menu_values, &self.checkboxes, &self.radios] {
+            for each in list {
+                let query = self.value_query(each).unwrap();
+                let x = self.read_value(each, &query);
+                let key = self.registry.key(each).unwrap().to_string();
+                let valid = self.checks.get(each).map(|check| self.run_check(each, check.as_ref(), &x));
+                ret_values.push((key.clone(), x));
+                if let Some(valid) = valid {
+                    ret_values.push((key + VALID_KEY_SUFFIX, valid.to_string()));
+                }
+            }
+        }
+        if let Some((source, text)) = &*self.last_drop.borrow() {
+            ret_values.push((DROP_SOURCE_KEY.to_string(), source.clone()));
+            ret_values.push((DROP_TEXT_KEY.to_string(), text.clone()));
+        }
+        *self.cached_values.borrow_mut() = ret_values.clone();
+        return ret_values;
+    }
+
+    // Values counted by `is_dirty`.
+    fn tracked_values(&self) -> HashMap<String, String> {
+        return self.values().into_iter()
+        .filter(|(key, _)| !self.untracked.contains(key) && key != DROP_SOURCE_KEY && key != DROP_TEXT_KEY)
+        .collect();
+    }
+
+    /// Takes the current values as the saved state `is_dirty` compares
+    /// against, as done when the window is created.
+    pub fn mark_clean(&mut self) {
+        self.clean_values = self.tracked_values();
+    }
+
+    /// Whether any value-bearing element, except those built with
+    /// `track_changes: false`, differs from the last `mark_clean`.
+    pub fn is_dirty(&self) -> bool {
+        return self.tracked_values() != self.clean_values;
+    }
+
+    /// Asks "unsaved changes - close anyway?" before the window is closed
+    /// while [`Window::is_dirty`]; closing is only reported by `read()` once
+    /// confirmed. Kiosk windows ignore this and keep refusing to close.
+    pub fn set_confirm_close(&self, confirm: bool) {
+        if self.kiosk {
+            return;
+        }
+        let action = if confirm { "close-request" } else { "exit" };
+        rstk::tell_wish(&format!("wm protocol . WM_DELETE_WINDOW {{ puts stdout {{{}}} ; flush stdout }}", action));
+    }
+
+    fn confirm_discard(&self) -> bool {
+        let _dialog = handle::ModalGuard::new(&self.events);
+        let answer = rstk::ask_wish(
+            "puts [tk_messageBox -parent . -type yesno -icon warning -title {Unsaved changes} \
+             -message {You have unsaved changes. Close anyway?}] ; flush stdout"
+        );
+        return answer.trim() == "yes";
+    }
+
+    // Runs `query`, from `value_query` or `link_query`, and returns the
+    // value of the element as `read()` reports it.
+    fn read_value(&self, id: &String, query: &str) -> String {
+        let value = rstk::ask_wish(&format!("puts [{}] ; flush stdout", query));
+        if let Some(RsgObjType::NumberInput) = self.element_types.get(id) {
+            return number_input_value(&value, decimal_separator());
+        }
+        return value;
+    }
+
+    // Tcl returning the value of the element, if it has one.
+    fn value_query(&self, id: &String) -> Option<String> {
+        if let Some(RsgObjType::NumberInput) = self.element_types.get(id) {
+            return Some(number_input_query(id));
+        } else if self.checklists.contains(id) {
+            return Some(checklist_query(&self.value_vars[id]));
+        } else if self.inputs.contains(id) {
+            return Some(format!("{} get 0.0 end", id));
+        } else if self.sliders.contains(id) || self.spins.contains(id) {
+            return Some(format!("{} get", id));
+        } else if self.editables.contains(id) {
+            return Some(format!("{} cget -text", id));
+        } else if let Some(entry) = self.kv_entries.get(id) {
+            return Some(format!("{} get", entry));
+        } else if self.checkboxes.contains(id) {
+            return Some(checkbox_query(id));
+        } else if self.radios.contains(id) {
+            // the group's variable only exists once one of its radios is selected
+            return Some(format!("expr {{[info exists [{} cget -variable]] && [set [{} cget -variable]] eq [{} cget -value] ? \"true\" : \"false\"}}", id, id, id));
+        } else if self.menu_checks.contains(id) {
+            return Some(format!("expr {{${} ? \"true\" : \"false\"}}", self.value_vars[id]));
+        } else if let Some(var) = self.value_vars.get(id) {
+            return Some(format!("set {}", var));
+        }
+        return None;
+    }
+
+    /// Keys of every element, in layout order. Elements showing a name are
+    /// keyed by it, the others get generated keys such as "input_0".
+    pub fn keys(&self) -> Vec<String> {
+        return self.registry.keys().to_vec();
+    }
+
+    /// Returns the Tk path of the element, for use with the low-level
+    /// `rstk::tell_wish` API.
+    pub fn widget_id(&self, key: &str) -> Result<&str, RsgError> {
+        return self.registry.id(key)
+        .ok_or(RsgError::UnknownKey(key.to_string()));
+    }
+
+    /// Returns the metadata set on the element with
+    /// [`RsgObj::set_metadata`], if any.
+    pub fn metadata(&self, key: &str) -> Result<Option<&str>, RsgError> {
+        let id = self.widget_id(key)?;
+        return Ok(self.element_metadata.get(id).map(|metadata| metadata.as_str()));
+    }
+
+    /// Raises the element above its siblings.
+    ///
+    /// Elements placed in the same grid cell overlap rather than push each
+    /// other aside: Tk draws them in stacking order, which is creation order
+    /// (later elements on top) adjusted by each element's `layer` when the
+    /// window is built. Raising or lowering only changes which one is drawn
+    /// on top, never the grid placement itself.
+    pub fn raise_element(&self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        rstk::tell_wish(&format!("raise {}", id));
+        return Ok(());
+    }
+
+    /// Lowers the element beneath its siblings, see [`Window::raise_element`].
+    pub fn lower_element(&self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        rstk::tell_wish(&format!("lower {}", id));
+        return Ok(());
+    }
+
+    /// Moves a progress bar to `value`, out of the maximum taken from its
+    /// `range`. Can be called between `read()`s to report on a running task.
+    pub fn update_progress(&self, key: &str, value: impl Into<f64>) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        rstk::tell_wish(&progress_command(id, value.into()));
+        return Ok(());
+    }
+
+    /// Points the needle of a [`gauge`] at `value`; values outside its
+    /// range are shown at the nearest end.
+    pub fn set_gauge(&self, key: &str, value: impl Into<f64>) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        let gauge = self.gauges.get(id).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        rstk::tell_wish(&gauge_command(id, *gauge, value.into()));
+        return Ok(());
+    }
+
+    /// Disables or re-enables an element. Custom colors are muted towards
+    /// the background while the element is disabled, see
+    /// [`Window::set_disabled_blend`]. Disabled elements report no events.
+    pub fn set_disabled(&self, key: &str, disabled: bool) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        let r#type = self.element_types.get(id).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        if disabled {
+            self.disabled.borrow_mut().insert(id.to_string());
+        } else {
+            self.disabled.borrow_mut().remove(id);
+        }
+        let color = self.element_colors.get(id).copied().unwrap_or((RsgColor::None, RsgColor::None));
+        let background = tk_rgb(&rstk::ask_wish("puts [. cget -background] ; flush stdout"));
+        let rgb = |c: RsgColor| tk_rgb(&get_rsg_color(c));
+
+        for command in state_commands(id, r#type, color, &rgb, background, self.disabled_blend, disabled) {
+            rstk::tell_wish(&command);
+        }
+        return Ok(());
+    }
+
+    /// How far the colors of disabled elements are blended towards the
+    /// background: 0.0 keeps them unchanged, 1.0 fades them out completely.
+    pub fn set_disabled_blend(&mut self, blend: f64) {
+        self.disabled_blend = blend.clamp(0.0, 1.0);
+    }
+
+    /// Switches to (or back from) a denser look for small screens: smaller
+    /// standard fonts, halved pads, no space around separators, and the
+    /// elements marked `hide_in_compact` hidden. The widgets are adjusted in
+    /// place, so values and state are kept.
+    pub fn set_compact(&mut self, compact: bool) {
+        if self.normal_font_sizes.is_empty() {
+            for font in COMPACT_FONTS {
+                let size = rstk::ask_wish(&format!("puts [font configure {} -size] ; flush stdout", font));
+                if let Ok(size) = size.trim().parse::<i64>() {
+                    self.normal_font_sizes.push((font.to_string(), size));
+                }
+            }
+        }
+        for (font, size) in &self.normal_font_sizes {
+            let size = if compact { compact_font_size(*size) } else { *size };
+            rstk::tell_wish(&format!("font configure {} -size {}", font, size));
+        }
+
+        for (id, pad) in &self.element_pads {
+            let hide = self.compact_hidden.contains(id);
+            for command in compact_commands(id, &self.element_types[id], *pad, hide, compact) {
+                rstk::tell_wish(&command);
+            }
+            // grid configure brings a removed element back
+            if self.hidden.contains(id) {
+                rstk::tell_wish(&format!("grid remove {}", id));
+            }
+        }
+    }
+
+    /// Shows or hides an element. A hidden element keeps its grid cell, its
+    /// value and its state, and comes back where it was.
+    pub fn set_visible(&mut self, key: &str, visible: bool) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        if visible {
+            rstk::tell_wish(&format!("grid {}", id));
+            self.hidden.remove(&id);
+        } else {
+            rstk::tell_wish(&format!("grid remove {}", id));
+            self.hidden.insert(id);
+        }
+        return Ok(());
+    }
+
+    /// Replaces the value shown by a [`kv`] row.
+    pub fn update_kv(&self, key: &str, value: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        let entry = self.kv_entries.get(id).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        rstk::tell_wish(&readonly_entry_command(entry, value));
+        return Ok(());
+    }
+
+    /// Changes the text shown by a text, button, checkbox, radio or status
+    /// bar. The element keeps its key; elements reporting events by name
+    /// report the new text from then on.
+    pub fn update_text(&mut self, key: &str, text: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        match self.element_types.get(&id) {
+            Some(RsgObjType::Text) | Some(RsgObjType::Button) | Some(RsgObjType::CheckBox) | Some(RsgObjType::Radio) | Some(RsgObjType::StatusBar) => {}
+            _ => return Err(RsgError::WrongElementType(key.to_string()))
+        }
+        rstk::tell_wish(&format!("{} configure -text {}", id, tcl_string(text)));
+        self.registry.rename(&id, text);
+        return Ok(());
+    }
+
+    /// Changes the foreground and background colors of an element;
+    /// `RsgColor::None` leaves that color as it is. A disabled element gets
+    /// the new colors muted until it is enabled again.
+    pub fn set_colors(&mut self, key: &str, color: (RsgColor, RsgColor)) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        let commands = self.element_types.get(&id).and_then(|t| recolor_commands(&id, t, color, self.respect_option_db))
+        .ok_or(RsgError::WrongElementType(key.to_string()))?;
+        for command in commands {
+            rstk::tell_wish(&command);
+        }
+        let current = self.element_colors.get(&id).copied().unwrap_or((RsgColor::None, RsgColor::None));
+        let fg = if let RsgColor::None = color.0 { current.0 } else { color.0 };
+        let bg = if let RsgColor::None = color.1 { current.1 } else { color.1 };
+        self.element_colors.insert(id.clone(), (fg, bg));
+        if self.disabled.borrow().contains(&id) {
+            return self.set_disabled(key, true);
+        }
+        return Ok(());
+    }
+
+    /// Sets the value shown by an input, slider, spin, progress bar,
+    /// checkbox, kv row, segmented button, radio group or option menu, or
+    /// the text of a text element. Checkboxes and checkable menu entries take
+    /// "true" or "false", menu radio groups the label of the entry to select.
+    pub fn set_value(&self, key: &str, value: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        let command = self.set_value_command(&id, value).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        rstk::tell_wish(&command);
+        return Ok(());
+    }
+
+    /// Returns a handle for updating one element, see [`RsgElement`].
+    pub fn element(&self, key: &str) -> Result<RsgElement, RsgError> {
+        let id = self.widget_id(key)?;
+        return Ok(RsgElement::new(key, id));
+    }
+
+    /// Moves the keyboard focus to the element.
+    pub fn set_focus(&self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        rstk::tell_wish(&format!("focus {}", id));
+        return Ok(());
+    }
+
+    /// Checks a checkbox with "true", unchecks it with "false", or shows a
+    /// tristate checkbox as "mixed".
+    pub fn update_checkbox(&self, key: &str, value: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        if !matches!(self.element_types.get(id), Some(RsgObjType::CheckBox)) {
+            return Err(RsgError::WrongElementType(key.to_string()));
+        }
+        if let Some(command) = default_command(id, &RsgObjType::CheckBox, value) {
+            rstk::tell_wish(&command);
+        }
+        return Ok(());
+    }
+
+    /// Replaces the picture of an image element with encoded data in memory,
+    /// at the size the element was given. Fails with [`RsgError::ImageLoad`]
+    /// and Tk's message when Tk cannot read the data.
+    pub fn update_image(&self, key: &str, data: &[u8]) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        if !matches!(self.element_types.get(id), Some(RsgObjType::Image)) {
+            return Err(RsgError::WrongElementType(key.to_string()));
+        }
+        let size = self.image_sizes.get(id).copied().unwrap_or((0, 0));
+        let img = load_image(ImageSource::Bytes(data), size).map_err(RsgError::ImageLoad)?;
+        rstk::tell_wish(&format!("{} configure -image {} -text {{}}", id, img));
+        return Ok(());
+    }
+
+    /// Returns a handle for drawing on a [`canvas`] element.
+    pub fn canvas(&self, key: &str) -> Result<RsgCanvas, RsgError> {
+        let id = self.widget_id(key)?;
+        if let Some(RsgObjType::Canvas) = self.element_types.get(id) {
+            return Ok(RsgCanvas::new(id));
+        }
+        return Err(RsgError::WrongElementType(key.to_string()));
+    }
+
+    fn graph(&self, key: &str) -> Result<&Graph, RsgError> {
+        let id = self.widget_id(key)?;
+        return self.graphs.get(id).ok_or(RsgError::WrongElementType(key.to_string()));
+    }
+
+    /// Adds points to a [`graph`], joined by a line in the order given.
+    /// Points added by later calls continue the same line.
+    pub fn graph_plot(&self, key: &str, points: &[(f64, f64)]) -> Result<(), RsgError> {
+        return self.graph_plot_series(key, GRAPH_SERIES, points);
+    }
+
+    /// Same as [`Window::graph_plot`] for a named data series, so that one
+    /// graph can show several lines.
+    pub fn graph_plot_series(&self, key: &str, series: &str, points: &[(f64, f64)]) -> Result<(), RsgError> {
+        let graph = self.graph(key)?;
+        graph.plot.plot_list(&tcl_string(series), points, false);
+        return Ok(());
+    }
+
+    /// Sets the line color of a data series; the default series of
+    /// [`Window::graph_plot`] is named "data".
+    pub fn graph_series_color(&mut self, key: &str, series: &str, color: RsgColor) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        let graph = self.graphs.get_mut(&id).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        let series = tcl_string(series);
+        let color = format!("{{{}}}", get_rsg_color(color));
+        rstk::TkChartSeries::series_colour(&graph.plot, &series, &color);
+        graph.colors.retain(|(s, _)| *s != series);
+        graph.colors.push((series, color));
+        return Ok(());
+    }
+
+    /// Removes every point from a graph, keeping its axes and series
+    /// colors, e.g. to redraw it from scratch after each `read()`.
+    pub fn graph_clear(&self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        let graph = self.graph(key)?;
+        let var = &graph.plot.id;
+        rstk::TkPlotchart::erase(&graph.plot);
+        rstk::tell_wish(&format!(
+            "global {}; set {} [::Plotchart::createXYPlot {} {{{} {} {}}} {{{} {} {}}}]",
+            var, var, id,
+            graph.x_axis.0, graph.x_axis.1, graph.x_axis.2,
+            graph.y_axis.0, graph.y_axis.1, graph.y_axis.2
+        ));
+        for (series, color) in &graph.colors {
+            rstk::TkChartSeries::series_colour(&graph.plot, series, color);
+        }
+        return Ok(());
+    }
+
+    /// Appends a line of text to an [`output`] element.
+    pub fn print(&self, key: &str, text: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        let max_lines = self.outputs.get(id).ok_or(RsgError::WrongElementType(key.to_string()))?;
+        rstk::tell_wish(&output_command(id, &format!("{}\n", text), *max_lines));
+        return Ok(());
+    }
+
+    /// Appends a line to a [`scrolled_text`], scrolling to it unless the
+    /// user has scrolled up to read earlier lines.
+    pub fn append_text(&self, key: &str, line: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        if !matches!(self.element_types.get(id), Some(RsgObjType::ScrolledText)) {
+            return Err(RsgError::WrongElementType(key.to_string()));
+        }
+        rstk::tell_wish(&append_text_command(id, line));
+        return Ok(());
+    }
+
+    /// Chooses an option of a [`segmented`] bar, without reporting an event.
+    /// A value that is not one of its options leaves every segment released.
+    pub fn update_segmented(&self, key: &str, selected: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        if !matches!(self.element_types.get(id), Some(RsgObjType::Segmented)) {
+            return Err(RsgError::WrongElementType(key.to_string()));
+        }
+        rstk::tell_wish(&format!("set {} {}", self.value_vars[id], tcl_string(selected)));
+        return Ok(());
+    }
+
+    /// Makes a [`color_chooser`] also set the background of the element
+    /// with `target_key` whenever a color is picked.
+    pub fn set_color_target(&self, chooser_key: &str, target_key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(chooser_key)?;
+        let target = self.widget_id(target_key)?;
+        if !matches!(self.element_types.get(id), Some(RsgObjType::ColorChooser)) {
+            return Err(RsgError::WrongElementType(chooser_key.to_string()));
+        }
+        let enable_events = self.color_chooser_events.contains(id);
+        let command = color_chooser_command(id, &self.value_vars[id], Some(target), enable_events);
+        rstk::tell_wish(&format!("{} configure -command {{ {} }}", id, command));
+        return Ok(());
+    }
+
+    /// Gives the window a menu bar, or replaces its entries. Checkable
+    /// entries and radio groups add their state to the values, and
+    /// accelerators work wherever the focus is. [`Window::swap_layout`]
+    /// removes the menu bar with the rest of the window.
+    pub fn set_menu_bar(&mut self, items: impl IntoIterator<Item = RsgMenuItem>) {
+        if !self.submenus.contains_key("") {
+            let bar = rstk::next_wid(".");
+            rstk::tell_wish(&format!("menu {} -tearoff 0 ; . configure -menu {}", bar, bar));
+            self.submenus.insert(String::new(), bar);
+        }
+        let _ = self.update_menu("", items);
+    }
+
+    /// Replaces the entries of the submenu at `path`, e.g. a recent-files
+    /// list. Radio groups keep their chosen value.
+    pub fn update_menu(&mut self, path: &str, items: impl IntoIterator<Item = RsgMenuItem>) -> Result<(), RsgError> {
+        let menu = self.submenus.get(path).ok_or(RsgError::UnknownKey(path.to_string()))?.clone();
+        let inside = |p: &str| path.is_empty() || p.starts_with(&format!("{}/", path));
+
+        let old: Vec<String> = self.menu_paths.keys().filter(|p| inside(p)).cloned().collect();
+        for p in old {
+            let id = self.menu_paths.remove(&p).unwrap();
+            self.menu_entries.remove(&id);
+            self.menu_checks.remove(&id);
+            self.menu_values.retain(|v| v != &id);
+            self.value_vars.remove(&id);
+            if let Some(key) = self.registry.key(&id).map(|key| key.to_string()) {
+                self.registry.remove(&key);
+            }
+            self.retired.insert(id);
+        }
+        let old: Vec<String> = self.submenus.keys().filter(|p| inside(p)).cloned().collect();
+        for p in old {
+            let sub = self.submenus.remove(&p).unwrap();
+            rstk::tell_wish(&format!("destroy {}", sub));
+        }
+
+        rstk::tell_wish(&format!("{} delete 0 end", menu));
+        self.fill_menu(&menu, path, items.into_iter().collect());
+        return Ok(());
+    }
+
+    fn fill_menu(&mut self, menu: &str, prefix: &str, items: Vec<RsgMenuItem>) {
+        for (index, item) in items.into_iter().enumerate() {
+            if let RsgMenuKind::Separator = item.kind {
+                rstk::tell_wish(&menu_entry_command(menu, &item, "", ""));
+                continue;
+            }
+            let path = if prefix.is_empty() { item.label.clone() } else { format!("{}/{}", prefix, item.label) };
+            let id = rstk::next_wid(menu);
+            let mut var = String::new();
+
+            match &item.kind {
+                RsgMenuKind::Check(checked) => {
+                    var = rstk::next_var();
+                    rstk::tell_wish(&format!("set {} {}", var, if *checked { 1 } else { 0 }));
+                    self.value_vars.insert(id.clone(), var.clone());
+                    self.menu_checks.insert(id.clone());
+                    self.menu_values.push(id.clone());
+                }
+                RsgMenuKind::Radio(group) => {
+                    if !self.menu_groups.contains_key(group) {
+                        let group_id = rstk::next_wid(".");
+                        let group_var = rstk::next_var();
+                        rstk::tell_wish(&format!("set {} {{}}", group_var));
+                        let key = self.registry.unique_key(group);
+                        self.registry.insert(key, group_id.clone());
+                        self.value_vars.insert(group_id.clone(), group_var);
+                        self.menu_values.push(group_id.clone());
+                        self.menu_groups.insert(group.clone(), group_id);
+                    }
+                    var = self.value_vars[&self.menu_groups[group]].clone();
+                }
+                RsgMenuKind::Submenu => rstk::tell_wish(&format!("menu {} -tearoff 0", id)),
+                _ => {}
+            }
+            rstk::tell_wish(&menu_entry_command(menu, &item, &id, &var));
+            self.menu_entries.insert(id.clone(), (menu.to_string(), index as u64));
+            self.menu_paths.insert(path.clone(), id.clone());
+
+            if let RsgMenuKind::Submenu = item.kind {
+                self.submenus.insert(path.clone(), id.clone());
+                self.fill_menu(&id, &path, item.items);
+                continue;
+            }
+
+            let key = self.registry.unique_key(if item.key.is_empty() { &path } else { &item.key });
+            self.registry.insert(key, id.clone());
+            self.registry.set_event_name(&id, &path);
+            if let Some(sequence) = accelerator_sequence(&item.accelerator) {
+                rstk::tell_wish(&format!("bind all {} {{ {} invoke {} }}", sequence, menu, index));
+            }
+        }
+    }
+
+    /// Enables or greys out the menu bar entry with the given path or key.
+    pub fn set_menu_item_enabled(&self, path: &str, enabled: bool) -> Result<(), RsgError> {
+        let id = match self.menu_paths.get(path) {
+            Some(id) => id.as_str(),
+            None => self.widget_id(path)?
+        };
+        let (menu, index) = self.menu_entries.get(id).ok_or(RsgError::WrongElementType(path.to_string()))?;
+        rstk::tell_wish(&format!("{} entryconfigure {} -state {}", menu, index, if enabled { "normal" } else { "disabled" }));
+        return Ok(());
+    }
+
+    /// Lets the user drag the element with the given key onto elements
+    /// accepting drops, see [`Window::accept_drop`]. What is dragged is the
+    /// element's text.
+    pub fn enable_drag(&self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?;
+        rstk::tell_wish(&drag_source_command(id));
+        return Ok(());
+    }
+
+    /// Makes dropping a dragged element onto the element with the given key
+    /// report e.g. "trash:::dropped" from `read()`, with the key of the
+    /// dragged element and its text under [`DROP_SOURCE_KEY`] and
+    /// [`DROP_TEXT_KEY`] in the values.
+    pub fn accept_drop(&mut self, key: &str) -> Result<(), RsgError> {
+        let id = self.widget_id(key)?.to_string();
+        rstk::tell_wish(&format!("set ::rsg_drop_targets({}) 1", id));
+        self.drop_targets.insert(id);
+        return Ok(());
+    }
+
+    /// Changes the text of the window's [`status_bar`]; does nothing if it
+    /// has none.
+    pub fn set_status(&self, text: &str) {
+        if let Some(id) = &self.status_bar {
+            rstk::tell_wish(&format!("{} configure -text {}", id, tcl_string(text)));
+        }
+    }
+
+    /// Shows `items` as a column of labels below the layout, keyed
+    /// "<key_prefix>_0", "<key_prefix>_1" and so on. Calling it again with
+    /// the same prefix updates the rows in place: missing rows are added and
+    /// surplus ones hidden, without leaving gaps. The rows share one frame,
+    /// placed below the rows of the layout as it is at the first call.
+    ///
+    /// Clicking a row is reported by `read()` as "<key_prefix>_<n>:::<n>".
+    /// A row whose key is already taken gets a "_<n>" suffix, as in layouts.
+    pub fn bind_list(&mut self, key_prefix: &str, items: &[String]) {
+        if !self.lists.contains_key(key_prefix) {
+            let frame = rstk::make_frame(&self.root);
+            rstk::tell_wish(&format!("grid {} -row [lindex [grid size .] 1] -column 0 -columnspan [expr {{max(1, [lindex [grid size .] 0])}}] -sticky w",
+                                     frame.id()));
+            self.lists.insert(key_prefix.to_string(), (frame.id().to_string(), vec![]));
+        }
+        let (frame, rows) = self.lists.get_mut(key_prefix).unwrap();
+
+        for (i, item) in items.iter().enumerate() {
+            if let Some(id) = rows.get(i) {
+                rstk::tell_wish(&format!("{} configure -text {} ; grid {}", id, tcl_string(item), id));
+                continue;
+            }
+            let id = rstk::next_wid(frame);
+            rstk::tell_wish(&list_row_command(&id, i, item));
+
+            let key = self.registry.unique_key(&format!("{}_{}", key_prefix, i));
+            self.element_types.insert(id.clone(), RsgObjType::Text);
+            self.element_colors.insert(id.clone(), (RsgColor::None, RsgColor::None));
+            self.registry.insert(key, id.clone());
+            rows.push(id);
+        }
+        for id in rows.iter().skip(items.len()) {
+            rstk::tell_wish(&format!("grid remove {}", id));
+        }
+    }
+
+    /// Replaces every element with a new layout, in the same toplevel, so
+    /// its geometry, title and other window settings stay as they are.
+    ///
+    /// Keys of the old layout stop working: methods given one return
+    /// [`RsgError::UnknownKey`], and events still pending from the old
+    /// widgets are dropped. Links are removed, event hooks are kept, and
+    /// handles from [`Window::handle`] must be fetched again.
+    pub fn swap_layout(&mut self, new_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) {
+        let layout = collect_layout(new_layout);
+        for issue in validate_layout(&layout) {
+            warn(&issue.to_string());
+        }
+        if let Some(handle) = self.handle.get() {
+            handle.flush();
+        }
+
+        rstk::start_recording();
+        rstk::tell_wish("foreach w [winfo children .] { destroy $w }");
+        *LAST_OUTPUT.lock().unwrap() = None;
+
+        let window_ex = RsgWindowEx{ element_gap: self.element_gap, min_touch_size: self.min_touch_size,
+                                     respect_option_db: self.respect_option_db,
+                                     kiosk: self.kiosk, ..Default::default() };
+        let fresh = blank_window(self.name.clone(), layout, self.root.clone(), window_ex, self.events.clone());
+        let old = std::mem::replace(self, fresh);
+        for img in old.button_images {
+            rstk::tell_wish(&format!("image delete {}", img));
+        }
+        self.retired = old.retired;
+        self.retired.extend(old.registry.iter().map(|(_, id)| id.to_string()));
+        self.hooks = old.hooks;
+        self.validators = old.validators;
+        self.disabled_blend = old.disabled_blend;
+        self.normal_font_sizes = old.normal_font_sizes;
+        self.icon = old.icon;
+
+        self.populate();
+    }
+
+    /// Returns the Tcl script that built the window, exactly as it was sent
+    /// to wish, one command per line. Pasted into a wish console it rebuilds
+    /// the window, which helps to bisect layouts that render wrong.
+    pub fn dump_tcl(&self) -> String {
+        return self.script.join("\n");
+    }
+
+    /// Returns a handle for updating the window from other threads, see
+    /// [`WindowHandle`].
+    pub fn handle(&self) -> WindowHandle {
+        return self.handle.get_or_init(|| {
+            let elements = self.registry.iter()
+                .map(|(key, id)| (key.to_string(), handle::HandleElement{
+                    id: id.to_string(),
+                    kv_entry: self.kv_entries.get(id).cloned(),
+                    gauge: self.gauges.get(id).cloned(),
+                    query: self.value_query(&id.to_string()),
+                    number_input: matches!(self.element_types.get(id), Some(RsgObjType::NumberInput))
+                }))
+                .collect();
+            WindowHandle::new(elements, self.events.clone())
+        }).clone();
+    }
+
+    /// Holds back changes to the window until the returned guard is dropped,
+    /// then applies them all at once with a single re-layout, so hiding or
+    /// showing many elements does not flicker. The window keeps its size
+    /// meanwhile. Anything asking wish for an answer, such as reading
+    /// values, sends the changes held so far first.
+    ///
+    /// Freezes nest: only dropping the outermost guard thaws the window.
+    pub fn freeze(&self) -> Frozen {
+        if FREEZE_DEPTH.fetch_add(1, Ordering::SeqCst) == 0 {
+            rstk::hold();
+            rstk::tell_wish("grid propagate . 0");
+        }
+        return Frozen{ _private: () };
+    }
+
+    /// Runs `changes` with the window frozen, see [`Window::freeze`]. The
+    /// window is thawed even if `changes` panics.
+    pub fn batch<R>(&mut self, changes: impl FnOnce(&mut Window) -> R) -> R {
+        let _frozen = self.freeze();
+        return changes(self);
+    }
+
+    /// Drops events injected through a [`WindowHandle`] while a native
+    /// dialog was open if, when it closes, they are older than `max_age`.
+    /// With `None`, the default, every such event is still reported.
+    pub fn set_stale_event_limit(&self, max_age: Option<std::time::Duration>) {
+        self.events.set_stale_after(max_age);
+    }
+
+    /// Changes the text in the window's title bar.
+    pub fn set_title(&mut self, title: &str) {
+        rstk::tell_wish(&title_command(self.root.id(), title));
+        self.name = title.to_string();
+    }
+
+    /// Resizes the window to `width` by `height` pixels. It keeps that size
+    /// from then on rather than fitting its content.
+    pub fn set_size(&self, width: u64, height: u64) {
+        rstk::tell_wish(&size_command(self.root.id(), (width, height)));
+    }
+
+    /// Makes the window fill the screen, keeping the window manager's
+    /// decorations.
+    pub fn maximize(&self) {
+        // X11 has no "zoomed" state, only the attribute
+        rstk::tell_wish(&format!("if {{[catch {{wm state {} zoomed}}]}} {{ wm attributes {} -zoomed 1 }}", self.root.id(), self.root.id()));
+    }
+
+    /// Minimizes the window to the task bar or dock. `read()` reports
+    /// [`RESTORED_KEY`] once the user brings it back.
+    pub fn minimize(&self) {
+        rstk::tell_wish(&format!("wm iconify {}", self.root.id()));
+    }
+
+    /// Brings a maximized or minimized window back to its usual size.
+    pub fn restore(&self) {
+        rstk::tell_wish(&format!("wm state {} normal ; catch {{wm attributes {} -zoomed 0}}", self.root.id(), self.root.id()));
+    }
+
+    /// Keeps the window above other windows, or lets it fall behind them
+    /// again.
+    pub fn set_keep_on_top(&self, keep_on_top: bool) {
+        rstk::tell_wish(&format!("wm attributes {} -topmost {}", self.root.id(), keep_on_top as u8));
+    }
+
+    /// Shows the picture file at `path` as the window's icon: a PNG or GIF,
+    /// or an ICO file on Windows. Fails with [`RsgError::ImageLoad`] and
+    /// Tk's message, keeping the current icon, when it cannot be loaded.
+    pub fn set_icon(&mut self, path: &str) -> Result<(), RsgError> {
+        if !path.to_lowercase().ends_with(".ico") {
+            return self.set_icon_image(ImageSource::Path(path));
+        }
+        // photo images cannot read ICO files, Windows takes them as bitmaps
+        let result = rstk::ask_wish(&format!("if {{[catch {{wm iconbitmap {} {}}} err]}} {{puts \"err $err\"}} else {{puts ok}} ; flush stdout",
+                                             self.root.id(), tcl_string(path)));
+        if result != "ok" {
+            return Err(RsgError::ImageLoad(result.trim_start_matches("err ").to_string()));
+        }
+        return Ok(());
+    }
+
+    /// Same as [`Window::set_icon`] with an encoded picture in memory, e.g.
+    /// from `include_bytes!`.
+    pub fn set_icon_data(&mut self, data: &[u8]) -> Result<(), RsgError> {
+        return self.set_icon_image(ImageSource::Bytes(data));
+    }
+
+    fn set_icon_image(&mut self, source: ImageSource) -> Result<(), RsgError> {
+        let img = load_image(source, (0, 0)).map_err(RsgError::ImageLoad)?;
+        rstk::tell_wish(&format!("wm iconphoto {} {}", self.root.id(), img));
+        if let Some(old) = self.icon.replace(img) {
+            rstk::tell_wish(&format!("image delete {}", old));
+        }
+        return Ok(());
+    }
+
+    /// Redraws the window now with every update made so far, including
+    /// those from handles not sent yet, instead of when the next event
+    /// comes. Events waiting for `read()` are left as they are; a frozen
+    /// window stays frozen.
+    pub fn refresh(&self) {
+        if let Some(handle) = self.handle.get() {
+            handle.flush();
+        }
+        rstk::tell_wish("update idletasks");
+    }
+
+    /// Shows the window above every other one and gives it the keyboard
+    /// focus, even while another window has it, e.g. when a background job
+    /// finishes.
+    pub fn bring_to_front(&self) {
+        let root = self.root.id();
+        rstk::tell_wish(&format!("wm deiconify {} ; raise {} ; focus -force {}", root, root, root));
+    }
+
+    /// Draws the user's eye to the window without taking the focus, by
+    /// fading it in and out a few times.
+    pub fn request_attention(&self) {
+        rstk::tell_wish(&format!("rsg_attention {}", self.root.id()));
+    }
+
+    /// Takes the window off the screen, keeping its elements and their
+    /// values until [`Window::un_hide`] shows it again. `read()` meanwhile
+    /// waits as usual, for events from other threads or a timeout.
+    pub fn hide(&self) {
+        rstk::tell_wish(&format!("wm withdraw {}", self.root.id()));
+    }
+
+    /// Shows a window hidden with [`Window::hide`] again.
+    pub fn un_hide(&self) {
+        rstk::tell_wish(&format!("wm deiconify {}", self.root.id()));
+    }
+
+    /// Keeps the user from shrinking the window below `width` by `height`
+    /// pixels.
+    pub fn set_min_size(&self, width: u64, height: u64) {
+        rstk::tell_wish(&format!("wm minsize {} {} {}", self.root.id(), width, height));
+    }
+
+    /// Keeps the user from growing the window beyond `width` by `height`
+    /// pixels.
+    pub fn set_max_size(&self, width: u64, height: u64) {
+        rstk::tell_wish(&format!("wm maxsize {} {} {}", self.root.id(), width, height));
+    }
+
+    /// Moves the window to `x`, `y` on the screen, negative values counting
+    /// from the right and bottom edges as with [`RsgWindowEx::location`].
+    pub fn move_to(&self, x: i64, y: i64) {
+        rstk::tell_wish(&location_command(self.root.id(), (x, y)));
+    }
+
+    /// Returns the window's width and height in pixels, or (0, 0) once it
+    /// was closed.
+    pub fn size(&self) -> (u64, u64) {
+        let answer = rstk::ask_wish(&format!("update idletasks ; puts \"[winfo width {}] [winfo height {}]\" ; flush stdout",
+                                             self.root.id(), self.root.id()));
+        let mut parts = answer.split_whitespace().map(|n| n.parse().unwrap_or(0));
+        return (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    }
+
+    /// Whether the window has been closed: `read()` has reported
+    /// [`WIN_CLOSED`], or wish has gone away. Reads then keep returning
+    /// [`WIN_CLOSED`] with the values last read.
+    pub fn was_closed(&self) -> bool {
+        return self.closed.get() || rstk::wish_gone();
+    }
+
+    /// Closes the window and ends the program. Does nothing once the window
+    /// was closed, see [`Window::was_closed`].
     pub fn close(&self) {
+        if self.was_closed() {
+            return;
+        }
+        if let Some(handle) = self.handle.get() {
+            handle.flush();
+        }
         rstk::end_wish()
     }
+}
+
+// Number of live Frozen guards.
+static FREEZE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps the window frozen while alive, see [`Window::freeze`].
+pub struct Frozen {
+    _private: ()
+}
+
+impl Frozen {
+    /// Same as dropping the guard.
+    pub fn thaw(self) {}
+}
+
+impl Drop for Frozen {
+    fn drop(&mut self) {
+        if FREEZE_DEPTH.fetch_sub(1, Ordering::SeqCst) == 1 {
+            rstk::tell_wish("grid propagate . 1");
+            rstk::release_held();
+        }
+    }
+}
+
+impl fmt::Debug for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elements: Vec<(&str, &str)> = self.registry.iter().collect();
+
+        return f.debug_struct("Window")
+        .field("name", &self.name)
+        .field("elements", &elements)
+        .finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_for_unnamed_elements() {
+        let layout = vec![ vec![text("What's your name?")],
+                           vec![input(), input()],
+                           vec![slider(), spin()],
+                           vec![button("Ok")] ];
+        let keys = assign_keys(&layout);
+        assert_eq!(vec!["What's your name?", "input_0", "input_1", "slider_0", "spin_0", "Ok"], keys);
+    }
+
+    #[test]
+    fn keys_are_deterministic() {
+        let layout = vec![ vec![input(), slider()], vec![input(), separator()] ];
+        assert_eq!(assign_keys(&layout), assign_keys(&layout.clone()));
+    }
+
+    #[test]
+    fn keys_follow_layout_position() {
+        let layout = vec![ vec![input_ex("first", RsgObjEx::default())],
+                           vec![slider()],
+                           vec![input_ex("second", RsgObjEx::default())] ];
+        let permuted = vec![ layout[2].clone(), layout[0].clone(), layout[1].clone() ];
+
+        assert_eq!(vec!["input_0", "slider_0", "input_1"], assign_keys(&layout));
+        assert_eq!(vec!["input_0", "input_1", "slider_0"], assign_keys(&permuted));
+    }
+
+    #[test]
+    fn keys_are_unique() {
+        let layout = vec![ vec![button("Ok"), button("Ok")],
+                           vec![text("input_0"), input()],
+                           vec![checkbox(""), checkbox("")] ];
+        assert_eq!(vec!["Ok", "Ok_1", "input_0", "input_0_1", "checkbox_0", "checkbox_1"], assign_keys(&layout));
+    }
+
+    fn test_rgb(color: RsgColor) -> (u8, u8, u8) {
+        match color {
+            RsgColor::Red => return (255, 0, 0),
+            RsgColor::Black => return (0, 0, 0),
+            _ => return (255, 255, 255),
+        }
+    }
+
+    #[test]
+    fn disable_cycle_mutes_and_restores_colors() {
+        let color = (RsgColor::Red, RsgColor::Black);
+        let grey = (200, 200, 200);
+
+        assert_eq!(vec![".r1 configure -state disabled",
+                        ".r1 configure -foreground #800000",
+                        ".r1 configure -disabledforeground #800000",
+                        ".r1 configure -background #646464"],
+                   state_commands(".r1", &RsgObjType::Button, color, &test_rgb, grey, 0.5, true));
+        assert_eq!(vec![".r1 configure -state normal",
+                        ".r1 configure -foreground {Red}",
+                        ".r1 configure -background {black}"],
+                   state_commands(".r1", &RsgObjType::Button, color, &test_rgb, grey, 0.5, false));
+    }
+
+    #[test]
+    fn disable_cycle_uses_style_map_for_ttk() {
+        let color = (RsgColor::Red, RsgColor::None);
+        let white = (255, 255, 255);
+
+        assert_eq!(vec!["ttk::style map Rsg_r2_r3.TCheckbutton -foreground [list disabled #ff8080]",
+                        ".r2.r3 configure -style Rsg_r2_r3.TCheckbutton",
+                        ".r2.r3 state disabled"],
+                   state_commands(".r2.r3", &RsgObjType::CheckBox, color, &test_rgb, white, 0.5, true));
+        assert_eq!(vec![".r2.r3 state !disabled"],
+                   state_commands(".r2.r3", &RsgObjType::CheckBox, color, &test_rgb, white, 0.5, false));
+    }
+
+    #[test]
+    fn disable_without_colors_only_changes_state() {
+        let none = (RsgColor::None, RsgColor::None);
+        assert_eq!(vec![".r1 state disabled"],
+                   state_commands(".r1", &RsgObjType::Text, none, &test_rgb, (0, 0, 0), 0.5, true));
+    }
+
+    #[test]
+    fn validate_accepts_a_clean_layout() {
+        let layout = vec![ vec![text("Name"), input()], vec![image_ex("logo.png", RsgObjEx{ size: (64, 64), ..Default::default() })] ];
+        assert!(validate_layout(&layout).is_empty());
+    }
+
+    #[test]
+    fn validate_reports_every_issue_with_its_position() {
+        let red = RsgObjEx{ color: (RsgColor::Red, RsgColor::None), ..Default::default() };
+        let inner = vec![ vec![button("Ok"), progress_bar_ex(RsgOrientation::Horizontal, red)] ];
+        let layout = vec![ vec![button("Ok"), image_ex("logo.png", RsgObjEx{ size: (64, 0), ..Default::default() })],
+                           vec![frame("Options", inner)] ];
+        assert_eq!(vec![
+            LayoutIssue{ row: 0, column: 1, container: None, kind: LayoutIssueKind::ZeroSizeImage },
+            LayoutIssue{ row: 0, column: 0, container: Some(String::from("frame_0")),
+                         kind: LayoutIssueKind::DuplicateKey{ key: String::from("Ok"), assigned: String::from("Ok_1") } },
+            LayoutIssue{ row: 0, column: 1, container: Some(String::from("frame_0")), kind: LayoutIssueKind::IgnoredColor },
+        ], validate_layout(&layout));
+    }
+
+    #[test]
+    fn validate_reports_radio_groups_without_members() {
+        let layout = vec![ vec![radio_ex("Small", RsgObjEx::new().group("size")), radio_ex("Large", RsgObjEx::new().group("size"))],
+                           vec![radio_ex("Dark", RsgObjEx::new().group("theme")), radio("Left"), radio("Right")],
+                           vec![radio_group(Vec::<String>::new(), 0)] ];
+        assert_eq!(vec![
+            LayoutIssue{ row: 1, column: 0, container: None, kind: LayoutIssueKind::EmptyRadioGroup{ group: String::from("theme") } },
+            LayoutIssue{ row: 2, column: 0, container: None, kind: LayoutIssueKind::EmptyRadioGroup{ group: String::from("radio_group_0") } },
+        ], validate_layout(&layout));
+    }
+
+    #[test]
+    fn compact_mode_halves_pads_and_hides_marked_elements() {
+        assert_eq!(vec!["grid configure .r1 -padx 5 -pady 2", "grid remove .r1"],
+                   compact_commands(".r1", &RsgObjType::Button, (10, 4), true, true));
+        assert_eq!(vec!["grid configure .r2 -padx 0 -pady 0"],
+                   compact_commands(".r2", &RsgObjType::Separator, (10, 4), false, true));
+        assert_eq!(vec!["grid .r1", "grid configure .r1 -padx 10 -pady 4"],
+                   compact_commands(".r1", &RsgObjType::Button, (10, 4), true, false));
+    }
+
+    #[test]
+    fn names_accept_any_string_type() {
+        let owned = String::from("Owned");
+        assert_eq!("Owned", text(&owned).name);
+        assert_eq!("Owned", button(owned.clone()).name);
+        assert_eq!("Cow", checkbox(std::borrow::Cow::Borrowed("Cow")).name);
+        assert_eq!(vec!["a", "b"], segmented([String::from("a"), String::from("b")]).options);
+    }
+
+    #[test]
+    fn layouts_accept_arrays_and_iterators() {
+        let nested = frame("Nested", [[text("a"), text("b")], [text("c"), text("d")]]);
+        assert_eq!(2, nested.layout.len());
+        assert_eq!(2, nested.layout[1].len());
+
+        let rows = column((0..3).map(|i| vec![text(format!("row {}", i))]));
+        assert_eq!("row 2", rows.layout[2][0].name);
+
+        // only has to compile: building a window needs wish
+        let _ = || window("Arrays", [[text("a")], [button("Ok")]]);
+        let _ = || window(String::from("Vecs"), vec![vec![text("a")]]);
+    }
+
+    #[test]
+    fn clicks_carry_button_and_modifiers() {
+        assert_eq!((".r3", 3, RsgModifiers{ shift: true, ctrl: false }), parse_click(".r3 3 1"));
+        assert_eq!((".r3", 1, RsgModifiers{ shift: false, ctrl: true }), parse_click(".r3 1 260"));
+        // injected events and older wish output are plain left clicks
+        assert_eq!((".r3", 1, RsgModifiers::default()), parse_click(".r3"));
+        assert_eq!(2, click_commands(".r3", false).len());
+        assert_eq!("bind .r3 <ButtonRelease-3> { puts \"clicked-.r3 %b %s\" ; flush stdout }", click_commands(".r3", true)[3]);
+    }
+
+    #[test]
+    fn kiosk_unlock_and_idle_cursor_are_optional() {
+        assert_eq!(vec!["wm attributes . -fullscreen 1 -topmost 1", "wm protocol . WM_DELETE_WINDOW {}"],
+                   kiosk_commands("", 0));
+        let commands = kiosk_commands("50%off", 3);
+        assert_eq!("bind all <KeyPress> {+rsg_kiosk_key %A \"50%%off\"}", commands[2]);
+        assert_eq!("bind all <Motion> {+rsg_kiosk_show 3000}", commands[3]);
+        assert_eq!("after 3000 rsg_kiosk_hide", commands[4]);
+    }
+
+    #[test]
+    fn recoloring_follows_how_elements_were_colored() {
+        assert_eq!(Some(vec![String::from(".r1 configure -fg {Red}"), String::from(".r1 configure -activebackground {Red}")]),
+                   recolor_commands(".r1", &RsgObjType::Button, (RsgColor::Red, RsgColor::None), true));
+        assert_eq!(Some(vec![String::from(".r2 configure -background {black}")]),
+                   recolor_commands(".r2", &RsgObjType::Spin, (RsgColor::None, RsgColor::Black), false));
+        assert_eq!(None, recolor_commands(".r3", &RsgObjType::Canvas, (RsgColor::Red, RsgColor::Black), false));
+    }
+
+    #[test]
+    fn only_the_window_reports_being_restored() {
+        assert_eq!(vec!["bind . <Unmap> {+if {\"%W\" eq \".\" && [wm state .] eq \"iconic\"} { set ::rsg_minimized 1 }}",
+                        "bind . <Map> {+if {\"%W\" eq \".\" && [info exists ::rsg_minimized]} { unset ::rsg_minimized ; puts restored ; flush stdout }}"],
+                   restore_event_commands("."));
+    }
+
+    #[test]
+    fn containers_grow_with_what_they_hold() {
+        let wide = input_ex("", RsgObjEx{ expand: (true, false), ..Default::default() });
+        let tall = input_ex("", RsgObjEx{ expand: (false, true), ..Default::default() });
+        assert_eq!((true, false), grows(&frame("Log", vec![vec![text("a"), wide.clone()]])));
+        assert_eq!((true, true), grows(&frame("Outer", vec![vec![frame("Inner", vec![vec![wide]])], vec![tall]])));
+        assert_eq!((false, false), grows(&frame("Fixed", vec![vec![text("a")]])));
+    }
+
+    #[test]
+    fn sizes_set_the_geometry() {
+        assert_eq!("wm geometry . 800x600", size_command(".", (800, 600)));
+        assert_eq!("wm geometry . +10+0", location_command(".", (10, 0)));
+        assert_eq!("wm geometry . -20+40", location_command(".", (-20, 40)));
+    }
+
+    #[test]
+    fn titles_are_quoted() {
+        assert_eq!("wm title . \"Report \\{draft\\} \\\"v2\\\" \\[50%\\]\"", title_command(".", "Report {draft} \"v2\" [50%]"));
+    }
+
+    #[test]
+    fn hover_colors_replace_the_derived_ones() {
+        assert_eq!(None, hover_command(".r1", (RsgColor::None, RsgColor::None)));
+        assert_eq!(Some(String::from("rsg_hover .r1 {white} {#202020}")),
+                   hover_command(".r1", (RsgColor::White, RsgColor::Hex("#202020"))));
+        assert_eq!(Some(String::from("rsg_hover .r1 {} {black}")), hover_command(".r1", (RsgColor::None, RsgColor::Black)));
+    }
+
+    #[test]
+    fn checkboxes_can_be_mixed() {
+        assert_eq!("expr {[set [.r1 cget -variable]] eq \"mixed\" ? \"mixed\" : [set [.r1 cget -variable]] ? \"true\" : \"false\"}",
+                   checkbox_query(".r1"));
+        assert_eq!(Some(String::from("set [.r1 cget -variable] mixed")), default_command(".r1", &RsgObjType::CheckBox, "mixed"));
+        assert_eq!(Some(String::from("set [.r1 cget -variable] 1")), default_command(".r1", &RsgObjType::CheckBox, "true"));
+    }
+
+    #[test]
+    fn radios_are_grouped_by_row_unless_named() {
+        // a row of one grid, whatever the padding
+        assert_eq!("__row0", radio_group_name(".", 0, None));
+        assert_ne!(radio_group_name(".", 0, None), radio_group_name(".", 1, None));
+        // the same row of another grid is another group
+        assert_ne!(radio_group_name(".", 0, None), radio_group_name(".r1", 0, None));
+        // named groups span rows and grids
+        assert_eq!(radio_group_name(".", 0, Some("size")), radio_group_name(".r1", 3, Some("size")));
+        assert_eq!("key_t_2d_20shirt", radio_group_name(".", 0, Some("t- shirt")));
+        assert_ne!(radio_group_name(".", 0, Some("a b")), radio_group_name(".", 0, Some("a_b")));
+    }
+
+    #[test]
+    fn builder_methods_set_one_field_each() {
+        let ex = RsgObjEx::new().size(10, 2).fg(RsgColor::Red).bg(RsgColor::Black).pad(4, 4).range(0, 10).key("go");
+        assert_eq!((10, 2), ex.size);
+        assert!(matches!(ex.color, (RsgColor::Red, RsgColor::Black)));
+        assert_eq!(((4, 4), (0, 10), Some("go")), (ex.pad, ex.range, ex.key));
+        assert_eq!(Some(String::from("go")), button_ex("Go", ex).key);
+        assert!(matches!(RsgObjEx::new().bg(RsgColor::Black).color, (RsgColor::None, RsgColor::Black)));
+    }
+
+    #[test]
+    fn element_gap_replaces_only_the_default_pad() {
+        assert_eq!((16, 8), element_pad(RsgObjEx::default().pad, (16, 8)));
+        assert_eq!((100, 10), element_pad(RsgObjEx::new().pad(100, 10).pad, (16, 8)));
+        assert_eq!((0, 0), element_pad((0, 0), (16, 8)));
+    }
+
+    #[test]
+    fn wrapped_texts_follow_their_width_when_stretched() {
+        assert_eq!(vec![".r1 configure -wraplength 200"], wrap_commands(".r1", 200, false));
+        assert_eq!(vec![".r1 configure -wraplength 200", "bind .r1 <Configure> {+%W configure -wraplength %w}"],
+                   wrap_commands(".r1", 200, true));
+    }
+
+    #[test]
+    fn unknown_cursors_are_reported_and_dropped() {
+        let mut layout = vec![ vec![text_ex("Link", RsgObjEx{ cursor: Some("hand2"), ..Default::default() }),
+                                    input_ex("", RsgObjEx{ cursor: Some("Xterm"), ..Default::default() })] ];
+        assert_eq!(vec![LayoutIssue{ row: 0, column: 1, container: None,
+                                     kind: LayoutIssueKind::InvalidCursor{ cursor: String::from("Xterm") } }],
+                   validate_layout(&layout));
+        drop_invalid_options(&mut layout);
+        assert_eq!(Some(String::from("hand2")), layout[0][0].cursor);
+        assert_eq!(None, layout[0][1].cursor);
+    }
+
+    #[test]
+    fn return_key_clicks_the_button_from_inputs_too() {
+        assert_eq!(vec!["bind . <Return> { .r3 invoke }",
+                        "bind .r1 <Return> { .r3 invoke ; break }",
+                        "bind .r2.r1 <Return> { .r3 invoke ; break }"],
+                   return_key_commands(".", ".r3", &[String::from(".r1"), String::from(".r2.r1")]));
+    }
+
+    #[test]
+    fn char_limits_stop_keys_and_cut_pastes() {
+        assert_eq!(vec!["bind .r1 <KeyPress> {+if {![rsg_char_room %W 8 %A]} break}",
+                        "bind .r1 <<Paste>> {rsg_char_paste %W 8 ; break}"],
+                   char_limit_commands(".r1", 8));
+    }
+
+    #[test]
+    fn borders_name_their_relief() {
+        assert_eq!("rsg_border .r1 0 flat", border_command(".r1", (0, RsgRelief::Flat)));
+        assert_eq!("rsg_border .r2 2 sunken", border_command(".r2", (2, RsgRelief::Sunken)));
+    }
+
+    #[test]
+    fn inputs_report_their_text_on_one_line() {
+        assert_eq!("bind .r1 <KeyRelease> {+puts \"cb1s-%W-[string map {\\n { }} [%W get 1.0 end-1c]]\" ; flush stdout}",
+                   input_events_command(".r1"));
+    }
+
+    #[test]
+    fn expanding_weights_the_row_and_column() {
+        assert!(expand_commands(".", ".r1", (2, 1), (false, false)).is_empty());
+        assert_eq!(vec!["grid configure .r1 -sticky ew", "grid columnconfigure . 1 -weight 1"],
+                   expand_commands(".", ".r1", (2, 1), (true, false)));
+        assert_eq!(vec!["grid configure .r2.r3 -sticky nsew", "grid columnconfigure .r2 0 -weight 1", "grid rowconfigure .r2 3 -weight 1"],
+                   expand_commands(".r2", ".r2.r3", (3, 0), (true, true)));
+    }
+
+    #[test]
+    fn spans_leave_out_elements_they_cover() {
+        let wide = RsgObjEx{ span: (3, 1), ..Default::default() };
+        let tall = RsgObjEx{ span: (1, 2), ..Default::default() };
+        let layout = vec![ vec![hseparator_ex(wide)],
+                           vec![button("A"), button("B"), button_ex("C", tall)],
+                           vec![button("D"), button("E")] ];
+        assert!(span_collisions(&layout).is_empty());
+
+        let layout = vec![ vec![hseparator_ex(wide), button("Covered")],
+                           vec![button_ex("A", tall), button("B")],
+                           vec![button("Also covered")] ];
+        assert_eq!(HashSet::from([(0, 1), (2, 0)]), span_collisions(&layout));
+        assert_eq!(LayoutIssueKind::SpanCollision, validate_layout(&layout)[0].kind);
+    }
+
+    #[test]
+    fn empty_spans_take_one_cell() {
+        assert_eq!(Some(String::from("grid configure .r1 -columnspan 1 -rowspan 2")),
+                   span_command(".r1", RsgObjEx::new().span(0, 2).span));
+        assert_eq!(Some(String::from("grid configure .r1 -columnspan 3 -rowspan 1")),
+                   span_command(".r1", RsgObjEx::new().span(3, 0).span));
+        assert_eq!(None, span_command(".r1", RsgObjEx::new().span(0, 0).span));
+        assert_eq!(None, span_command(".r1", RsgObjEx::new().span));
+    }
+
+    #[test]
+    fn invalid_hex_colors_are_reported_and_dropped() {
+        let bad = RsgObjEx{ color: (RsgColor::Hex("#12345"), RsgColor::Hex("#abc")), ..Default::default() };
+        let mut layout = vec![ vec![button_ex("Ok", bad)] ];
+        assert_eq!(vec![LayoutIssue{ row: 0, column: 0, container: None,
+                                     kind: LayoutIssueKind::InvalidColor{ color: String::from("#12345") } }],
+                   validate_layout(&layout));
+        drop_invalid_options(&mut layout);
+        assert!(matches!(layout[0][0].color, (RsgColor::None, RsgColor::Hex("#abc"))));
+    }
+
+    #[test]
+    fn justify_moves_the_element_and_its_text() {
+        assert!(justify_commands(".r1", &RsgObjType::Text, RsgJustify::Center).is_empty());
+        assert_eq!(vec!["grid configure .r1 -sticky w", ".r1 configure -anchor w -justify left"],
+                   justify_commands(".r1", &RsgObjType::Text, RsgJustify::Left));
+        assert_eq!(vec!["grid configure .r2 -sticky e", ".r2 configure -justify right"],
+                   justify_commands(".r2", &RsgObjType::NumberInput, RsgJustify::Right));
+        assert_eq!("bind .r3 <KeyRelease> {+%W tag add rsg_justify 1.0 end}",
+                   justify_commands(".r3", &RsgObjType::Input, RsgJustify::Right)[3]);
+    }
+
+    #[test]
+    fn defaults_depend_on_the_element() {
+        assert_eq!(Some(String::from("set [.r1 cget -variable] 1")), default_command(".r1", &RsgObjType::CheckBox, "true"));
+        assert_eq!(Some(String::from("set [.r1 cget -variable] 0")), default_command(".r1", &RsgObjType::CheckBox, "false"));
+        assert_eq!(Some(String::from("set [.r2 cget -variable] [.r2 cget -value]")), default_command(".r2", &RsgObjType::Radio, "true"));
+        assert_eq!(None, default_command(".r2", &RsgObjType::Radio, "false"));
+        assert_eq!(Some(String::from(".r3 set \"50\"")), default_command(".r3", &RsgObjType::Slider, "50"));
+        assert_eq!(None, default_command(".r3", &RsgObjType::Slider, ""));
+        assert_eq!(None, default_command(".r4", &RsgObjType::Input, "text"));
+    }
+
+    #[test]
+    fn tooltips_add_to_existing_bindings() {
+        let commands = tooltip_commands(".r1", "Saves [all] files");
+        assert_eq!("set ::rsg_tooltips(.r1) \"Saves \\[all\\] files\"", commands[0]);
+        assert_eq!("bind .r1 <Enter> {+rsg_tooltip_enter %W}", commands[1]);
+        assert_eq!("bind .r1 <Leave> {+rsg_tooltip_leave}", commands[2]);
+    }
+
+    #[test]
+    fn fonts_set_only_what_is_given() {
+        assert_eq!("rsg_font .r1 [list]", font_command(".r1", RsgFont::default()));
+        let font = RsgFont{ family: "DejaVu Sans", size: 14, bold: true, italic: true, underline: true };
+        assert_eq!("rsg_font .r1 [list -family \"DejaVu Sans\" -size 14 -weight bold -slant italic -underline 1]",
+                   font_command(".r1", font));
+    }
+
+    #[test]
+    fn explicit_keys_win_over_names() {
+        let mut apply = button("Ok");
+        apply.set_key("apply");
+        let layout = vec![ vec![button("Ok"), apply],
+                           vec![input_ex("", RsgObjEx{ key: Some("name"), ..Default::default() }), input()],
+                           vec![button_ex("Cancel", RsgObjEx{ key: Some("apply"), ..Default::default() })] ];
+        assert_eq!(vec!["Ok", "apply", "name", "input_0", "apply_1"], assign_keys(&layout));
+    }
+
+    #[test]
+    fn sliders_round_to_their_step() {
+        assert_eq!(vec!["scale .r1 -orient horizontal -from 0 -to 100 -resolution 1"], slider_commands(".r1", &slider()));
+        let fine = RsgObjEx{ range: (-5, 5), step: 0.5, tick_interval: 2.5, size: (200, 0), ..Default::default() };
+        assert_eq!(vec!["scale .r1 -orient horizontal -from -5 -to 5 -resolution 0.5 -tickinterval 2.5 -length 200"],
+                   slider_commands(".r1", &slider_ex(RsgOrientation::Horizontal, fine)));
+        let bare = RsgObjEx{ size: (20, 150), show_value: false, ..Default::default() };
+        assert_eq!(vec!["scale .r1 -orient vertical -from 0 -to 100 -resolution 1 -showvalue 0 -length 150 -width 20"],
+                   slider_commands(".r1", &vslider_ex(bare)));
+    }
+
+    #[test]
+    fn checklist_items_report_their_label_and_state() {
+        let commands = checklist_commands(".r1", "::var2", &[String::from("Warnings"), String::from("Errors")], 150);
+        assert_eq!("set ::var2(1) 0 ; set ::var2(label,1) \"Errors\" ; ttk::checkbutton .r1.c.f.r1 -text \"Errors\" -variable ::var2(1) \
+                    -command { puts cb1s-.r1-$::var2(label,1):::[expr {$::var2(1) ? \"true\" : \"false\"}] ; flush stdout } ; grid .r1.c.f.r1 -row 1 -column 0 -sticky w",
+                   commands.last().unwrap());
+        assert_eq!("join [lmap k [lsort -integer [array names ::var2 -regexp {^[0-9]+$}]] { if {[set ::var2($k)]} { set ::var2(label,$k) } else continue }] ,",
+                   checklist_query("::var2"));
+    }
+
+    #[test]
+    fn number_inputs_validate_keys_and_clamp_only_with_a_range() {
+        let unbounded = number_input_commands(".r1", 10, number_input().range, '.');
+        assert_eq!(vec!["ttk::entry .r1 -width 10 -validate key -validatecommand {regexp {^[-+]?[0-9]*[.]?[0-9]*$} %P}"], unbounded);
+        let bounded = number_input_commands(".r1", 10, (-5, 5), '.');
+        assert_eq!("bind .r1 <FocusOut> {rsg_number_clamp %W -5 5}", bounded[1]);
+        assert_eq!(".r1 get", number_input_query(".r1"));
+    }
+
+    #[test]
+    fn number_inputs_take_the_decimal_separator() {
+        let commands = number_input_commands(".r1", 10, (0, 0), ',');
+        assert_eq!(vec!["ttk::entry .r1 -width 10 -validate key -validatecommand {regexp {^[-+]?[0-9]*[.\\,]?[0-9]*$} %P}"], commands);
+        assert_eq!("3.5", number_input_value("3,5", ','));
+        assert_eq!("3.5", number_input_value("3.5", ','));
+        assert_eq!("-2", number_input_value("-2", '.'));
+        assert_eq!("", number_input_value("-", ','));
+        assert_eq!("", number_input_value("", ','));
+    }
+
+    #[test]
+    fn events_split_without_panicking_on_junk() {
+        assert_eq!((".r1", Some("a-cbsep-b"), 1, RsgModifiers::default()), split_event(".r1-cbsep-a-cbsep-b"));
+        assert_eq!((".r2", None, 3, RsgModifiers{ shift: true, ctrl: false }), split_event(".r2 3 1"));
+        let long = "x".repeat(16 * 1024);
+        for event in ["", " ", "-cbsep-", "\0-cbsep-\0", ".r1 \u{1F600} 99999999999999999999", ".r1 -1 -1", &long] {
+            let _ = split_event(event);
+        }
+    }
+
+    #[test]
+    fn gauge_needle_is_clamped_to_the_range() {
+        let gauge = Gauge{ range: (0, 100), width: 160 };
+        assert_eq!("rsg_gauge .r1 0 100 0", gauge_command(".r1", gauge, -5.0));
+        assert_eq!("rsg_gauge .r1 0 100 12.5", gauge_command(".r1", gauge, 12.5));
+        assert_eq!("rsg_gauge .r1 0 100 100", gauge_command(".r1", gauge, 250.0));
+        assert_eq!(vec![".r1 configure -width 160 -height 140 -highlightthickness 0", "set ::rsg_gauge_colour(.r1) {black}"],
+                   gauge_draw_commands(".r1", gauge, "black"));
+    }
+
+    #[test]
+    fn radio_groups_share_one_value() {
+        assert_eq!("Medium", radio_group(["Small", "Medium", "Large"], 1).value);
+        assert_eq!("", radio_group(["Small"], 3).value);
+        assert_eq!("ttk::radiobutton .r1.r3 -text \"Large\" -value \"Large\" -variable ::var2 \
+                    -command { puts cb1s-.r1-[set ::var2] ; flush stdout } ; grid .r1.r3 -row 0 -column 2 -sticky w",
+                   radio_group_command(".r1", ".r1.r3", "::var2", "Large", 2));
+    }
+
+    #[test]
+    fn drag_sources_get_the_drag_bindtag_first() {
+        assert_eq!("bindtags .r1.r3 [linsert [bindtags .r1.r3] 0 RsgDrag]", drag_source_command(".r1.r3"));
+    }
+
+    #[test]
+    fn invalid_marks_keep_the_background_to_restore() {
+        assert_eq!("if {![info exists ::rsg_valid_bg(.r1)] && ![catch {.r1 cget -background} bg]} \
+                    { set ::rsg_valid_bg(.r1) $bg ; .r1 configure -background #f8d7da }",
+                   invalid_mark_command(".r1", true));
+        assert_eq!("if {[info exists ::rsg_valid_bg(.r1)]} { .r1 configure -background $::rsg_valid_bg(.r1) ; unset ::rsg_valid_bg(.r1) }",
+                   invalid_mark_command(".r1", false));
+        assert_eq!(".r2 configure -text \"Name is empty\\nAge is not a number\"",
+                   summary_command(".r2", &RsgObjType::StatusBar, "Name is empty\nAge is not a number"));
+    }
+
+    #[test]
+    fn paned_sides_are_its_children() {
+        assert_eq!(vec!["foreach c [winfo children .r1] { .r1 add $c -weight 1 }"], paned_commands(".r1", 0));
+        assert_eq!("bind .r1 <Map> { bind .r1 <Map> {} ; after idle { .r1 sashpos 0 200 } }", paned_commands(".r1", 200)[1]);
+
+        let split = paned([[text("left")]], [[text("right")]], RsgOrientation::Vertical);
+        assert_eq!(vec!["paned_0", "column_0", "left", "column_1", "right"], assign_keys(&vec![vec![split]]));
+    }
+
+    #[test]
+    fn button_menu_entries_report_their_label() {
+        let entries = vec![String::from("Rename"), String::from("Move to {trash}")];
+        assert_eq!(vec!["ttk::menubutton .r1 -text \"Actions\" -menu .r1.r2",
+                        "menu .r1.r2 -tearoff 0",
+                        ".r1.r2 add command -label \"Rename\" -command { puts \"cb1s-.r1-Rename\" ; flush stdout }",
+                        ".r1.r2 add command -label \"Move to \\{trash\\}\" -command { puts \"cb1s-.r1-Move to \\{trash\\}\" ; flush stdout }"],
+                   button_menu_commands(".r1", ".r1.r2", "Actions", &entries));
+    }
+
+    #[test]
+    fn option_menus_report_the_choice() {
+        assert_eq!("Red", option_menu(["Red", "Blue"], "").value);
+        assert_eq!("Blue", option_menu(["Red", "Blue"], "Blue").value);
+        assert_eq!("set m [tk_optionMenu .r4 ::var5 \"Red\" \"Dark blue\"] ; for {set k 0} {$k <= [$m index end]} {incr k} { $m entryconfigure $k -command {puts cb1s-.r4-[set ::var5] ; flush stdout} }",
+                   option_menu_command(".r4", "::var5", &[String::from("Red"), String::from("Dark blue")]));
+    }
+
+    #[test]
+    fn custom_separators_get_a_style() {
+        assert_eq!(vec!["ttk::style configure Rsg_r1.TSeparator -background {gray50} -padding {0 0 0 2}",
+                        "ttk::style layout Rsg_r1.TSeparator {Separator.fill -sticky nsew -children {Separator.padding -sticky nsew}}",
+                        "ttk::separator .r1 -orient horizontal -style Rsg_r1.TSeparator"],
+                   styled_separator_commands(".r1", false, (0, 0), (RsgColor::None, RsgColor::None)));
+        assert_eq!("ttk::style configure Rsg_r1.TSeparator -background {black} -padding {0 0 4 120}",
+                   styled_separator_commands(".r1", true, (120, 4), (RsgColor::None, RsgColor::Black))[0]);
+        assert_eq!("ttk::separator .r1 -orient vertical -style Rsg_r1.TSeparator",
+                   styled_separator_commands(".r1", true, (120, 4), (RsgColor::None, RsgColor::Black))[2]);
+        assert_eq!("ttk::style configure Rsg_r1.TSeparator -background {violet red} -padding {0 0 80 3}",
+                   styled_separator_commands(".r1", false, (80, 3), (RsgColor::VioletRed, RsgColor::Black))[0]);
+    }
+
+    #[test]
+    fn image_data_is_base64_in_pieces() {
+        assert_eq!("", base64(b""));
+        assert_eq!("TWFu", base64(b"Man"));
+        assert_eq!("TWE=", base64(b"Ma"));
+        assert_eq!("TQ==", base64(b"M"));
+
+        let commands = image_data_commands("::d", &vec![0u8; IMAGE_CHUNK]);
+        assert_eq!("set ::d {}", commands[0]);
+        // 4 characters per 3 bytes, so two pieces
+        assert_eq!(3, commands.len());
+        assert!(commands[2].starts_with("append ::d AAAA"));
+    }
+
+    #[test]
+    fn list_rows_report_their_index() {
+        assert_eq!("label .r1.r2 -text \"a b\" ; grid .r1.r2 -row 3 -column 0 -sticky w ; bind .r1.r2 <Button-1> { puts cb1s-.r1.r2-3 ; flush stdout }",
+                   list_row_command(".r1.r2", 3, "a b"));
+    }
+
+    #[test]
+    fn default_colors_emit_no_commands() {
+        assert!(color_commands(".r1", (RsgColor::None, RsgColor::None), ("fg", "bg"), true).is_empty());
+        assert!(color_commands(".r1", (RsgColor::None, RsgColor::None), ("fg", "bg"), false).is_empty());
+    }
+
+    #[test]
+    fn option_db_keeps_unset_active_colors() {
+        assert_eq!(vec![".r1 configure -bg {black}", ".r1 configure -activeforeground {black}"],
+                   color_commands(".r1", (RsgColor::None, RsgColor::Black), ("fg", "bg"), true));
+        assert_eq!(vec![".r1 configure -activebackground [.r1 cget -fg]",
+                        ".r1 configure -bg {black}", ".r1 configure -activeforeground {black}"],
+                   color_commands(".r1", (RsgColor::None, RsgColor::Black), ("fg", "bg"), false));
+        assert_eq!(vec![".r1 configure -fg {Red}", ".r1 configure -activebackground {Red}",
+                        ".r1 configure -activeforeground [.r1 cget -fg]"],
+                   color_commands(".r1", (RsgColor::Red, RsgColor::None), ("bg", "fg"), false));
+    }
+
+    #[test]
+    fn touch_size_grows_elements_through_internal_pad() {
+        assert_eq!("after idle { if {[winfo exists .r1]} { grid configure .r1 -ipadx [expr {max(0, (48 - [winfo reqwidth .r1] + 1) / 2)}] \
+                    -ipady [expr {max(0, (40 - [winfo reqheight .r1] + 1) / 2)}] } }",
+                   min_size_command(".r1", (48, 40)));
+        assert!(is_touch_target(&RsgObjType::Button));
+        assert!(!is_touch_target(&RsgObjType::Text));
+        assert_eq!("font configure TkDefaultFont -size [expr {round([font configure TkDefaultFont -size] * 1.4)}]",
+                   font_scale_command("TkDefaultFont", RsgWindowEx::touch_mode().font_scale));
+    }
+
+    #[test]
+    fn compact_font_sizes_keep_their_unit() {
+        assert_eq!(8, compact_font_size(10));
+        assert_eq!(-10, compact_font_size(-12));
+        assert_eq!(1, compact_font_size(1));
+    }
+
+    #[test]
+    fn graph_axes_have_ten_ticks() {
+        assert_eq!((0.0, 100.0, 10.0), graph_axis((0, 100)));
+        assert_eq!((-5.0, 15.0, 2.0), graph_axis((-5, 15)));
+        assert_eq!((3.0, 3.0, 1.0), graph_axis((3, 3)));
+    }
+
+    #[test]
+    fn links_follow_chains_from_the_changed_element() {
+        let links = [("b", "c"), ("a", "b"), ("x", "y")];
+        assert_eq!(vec![1, 0], link_order(&links, "a"));
+        assert_eq!(Vec::<usize>::new(), link_order(&links, "none"));
+    }
+
+    #[test]
+    fn links_are_followed_breadth_first() {
+        let links = [("a", "b"), ("b", "c"), ("a", "d")];
+        assert_eq!(vec![0, 2, 1], link_order(&links, "a"));
+    }
+
+    #[test]
+    fn two_way_links_do_not_write_back() {
+        let links = [("a", "b"), ("b", "a")];
+        assert_eq!(vec![0], link_order(&links, "a"));
+        assert_eq!(vec![1], link_order(&links, "b"));
+        let links = [("a", "b"), ("b", "c"), ("c", "a")];
+        assert_eq!(vec![0, 1], link_order(&links, "a"));
+    }
+
+    #[test]
+    fn output_appends_a_line_and_caps_the_buffer() {
+        assert_eq!(".r1 configure -state normal ; .r1 insert end \"done\\n\" ; .r1 see end ; .r1 configure -state disabled",
+                   output_command(".r1", "done\n", 0));
+        assert!(output_command(".r1", "50%", 0).contains(".r1 insert end \"50%\" ;"));
+        let capped = output_command(".r1", "done\n", 100);
+        assert!(capped.contains("if {$n > 100} { .r1 delete 1.0 [expr {$n - 100 + 1}].0 }"));
+        assert!(capped.ends_with(".r1 see end ; .r1 configure -state disabled"));
+    }
+
+    #[test]
+    fn menu_entries_report_their_id() {
+        let save = RsgMenuItem{ accelerator: String::from("Ctrl+S"), ..menu_item("Save") };
+        assert_eq!(".r1 add command -label \"Save\" -command { puts clicked-.r1.r2 ; flush stdout } -accelerator \"Ctrl+S\"",
+                   menu_entry_command(".r1", &save, ".r1.r2", ""));
+        assert_eq!(".r1 add checkbutton -label \"Wrap\" -variable ::var3 -command { puts cb1b-.r1.r2-$::var3 ; flush stdout }",
+                   menu_entry_command(".r1", &menu_check("Wrap", true), ".r1.r2", "::var3"));
+        assert_eq!(".r1 add radiobutton -label \"Dark\" -variable ::var3 -value \"Dark\" -command { puts clicked-.r1.r2 ; flush stdout }",
+                   menu_entry_command(".r1", &menu_radio("Dark", "theme"), ".r1.r2", "::var3"));
+    }
+
+    // A window with a checkable menu entry, a menu radio group and a plain
+    // menu entry, and no widgets built.
+    fn menu_window() -> Window {
+        let root = rstk::TkTopLevel{ id: String::from(".") };
+        let mut window = blank_window(String::from("menus"), vec![], root, RsgWindowEx::default(), Arc::new(handle::EventQueue::default()));
+        window.registry.insert(String::from("Options/Autosave"), String::from(".r1.r2"));
+        window.menu_checks.insert(String::from(".r1.r2"));
+        window.value_vars.insert(String::from(".r1.r2"), String::from("::var1"));
+        window.registry.insert(String::from("theme"), String::from(".r3"));
+        window.value_vars.insert(String::from(".r3"), String::from("::var2"));
+        window.registry.insert(String::from("File/Quit"), String::from(".r1.r4"));
+        return window;
+    }
+
+    #[test]
+    fn a_batch_is_released_once() {
+        let mut window = menu_window();
+        window.registry.insert(String::from("a"), String::from(".r5"));
+        window.registry.insert(String::from("b"), String::from(".r6"));
+        rstk::start_recording();
+        window.batch(|window| {
+            let _nested = window.freeze();
+            window.set_visible("a", false).unwrap();
+            window.set_visible("b", false).unwrap();
+            window.set_visible("a", true).unwrap();
+            window.set_visible("b", true).unwrap();
+        });
+        let script = rstk::end_recording();
+        let geometry: Vec<&String> = script.iter().filter(|m| m.starts_with("grid .") || m.starts_with("grid remove")).collect();
+        assert_eq!(vec!["grid remove .r5", "grid remove .r6", "grid .r5", "grid .r6"], geometry);
+        // one freeze and one thaw, which releases everything held at once
+        assert_eq!(1, script.iter().filter(|m| *m == "grid propagate . 0").count());
+        assert_eq!(1, script.iter().filter(|m| *m == "grid propagate . 1").count());
+        assert_eq!(Some(&String::from("grid propagate . 1")), script.last());
+    }
+
+    #[test]
+    fn menu_keys_are_set_through_their_variables() {
+        let mut window = menu_window();
+        assert_eq!(Some(String::from("set ::var1 1")), window.set_value_command(&String::from(".r1.r2"), "true"));
+        assert_eq!(Some(String::from("set ::var2 \"Dark\"")), window.set_value_command(&String::from(".r3"), "Dark"));
+        assert_eq!(None, window.set_value_command(&String::from(".r1.r4"), "true"));
+        assert!(window.link("Options/Autosave", "theme", None).is_ok());
+        assert!(matches!(window.link("Options/Autosave", "File/Quit", None), Err(RsgError::WrongElementType(_))));
+
+        // menu entries are not elements, so element methods refuse them
+        assert!(matches!(window.set_disabled("Options/Autosave", true), Err(RsgError::WrongElementType(_))));
+        assert!(matches!(window.update_text("File/Quit", "Exit"), Err(RsgError::WrongElementType(_))));
+        assert!(matches!(window.set_colors("theme", (RsgColor::Red, RsgColor::None)), Err(RsgError::WrongElementType(_))));
+        assert!(matches!(window.update_checkbox("Options/Autosave", "true"), Err(RsgError::WrongElementType(_))));
+        assert!(matches!(window.set_value("File/Quit", "x"), Err(RsgError::WrongElementType(_))));
+    }
+
+    #[test]
+    fn element_handles_go_stale_with_their_element() {
+        let mut window = menu_window();
+        let autosave = window.element("Options/Autosave").unwrap();
+        let copy = autosave.clone();
+        assert_eq!(".r1.r2", autosave.widget_id(&window).unwrap());
+        assert!(matches!(copy.set_disabled(&window, true), Err(RsgError::WrongElementType(_))));
+
+        window.registry.remove("Options/Autosave");
+        window.registry.insert(String::from("Options/Autosave"), String::from(".r5"));
+        assert!(matches!(autosave.widget_id(&window), Err(RsgError::UnknownKey(_))));
+        assert!(matches!(autosave.set_value(&window, "true"), Err(RsgError::UnknownKey(_))));
+        assert!(matches!(window.element("missing"), Err(RsgError::UnknownKey(_))));
+    }
+
+    #[test]
+    fn accelerators_become_key_bindings() {
+        assert_eq!(Some(String::from("<Control-Key-s>")), accelerator_sequence("Ctrl+S"));
+        assert_eq!(Some(String::from("<Control-Shift-Key-S>")), accelerator_sequence("Ctrl+Shift+s"));
+        assert_eq!(Some(String::from("<Key-F5>")), accelerator_sequence("F5"));
+        assert_eq!(None, accelerator_sequence("Hyper+S"));
+        assert_eq!(None, accelerator_sequence(""));
+    }
+
+    #[test]
+    fn titlebar_closes_like_the_window_manager_and_drags_the_window() {
+        let commands = titlebar_commands(".r1", ".r1.r2", ".r1.r3", ".r1.r4");
+        assert!(commands.contains(&String::from("ttk::button .r1.r4 -text \"\\u2715\" -width 3 -command {eval [wm protocol . WM_DELETE_WINDOW]}")));
+        for w in [".r1", ".r1.r2"] {
+            assert!(commands.contains(&format!("bind {} <B1-Motion> {{rsg_drag_move %X %Y}}", w)));
+        }
+        assert!(!commands.iter().any(|c| c.contains("bind .r1.r3")));
+    }
+
+    #[test]
+    fn append_text_only_follows_the_end() {
+        let command = append_text_command(".r1.r2", "built");
+        assert!(command.starts_with("set ::rsg_at_end [expr {[lindex [.r1.r2 yview] 1] == 1.0}] ; .r1.r2 configure -state normal"));
+        assert!(command.contains(".r1.r2 insert end \"built\\n\""));
+        assert!(command.ends_with(".r1.r2 configure -state disabled ; if {$::rsg_at_end} { .r1.r2 see end }"));
+    }
+
+    #[test]
+    fn folder_browse_fills_its_target_only_when_a_directory_is_chosen() {
+        assert_eq!("set d [tk_chooseDirectory -parent .] ; set ::var1 $d",
+                   folder_browse_command(".r2", "::var1", "", None, false));
+        assert_eq!("set d [tk_chooseDirectory -parent . -initialdir \"/home/me\"] ; set ::var1 $d ; \
+                    if {$d ne \"\"} { .r1 delete 1.0 end ; .r1 insert end $d } ; puts cb1s-.r2-$d ; flush stdout",
+                   folder_browse_command(".r2", "::var1", "/home/me", Some(".r1"), true));
+    }
+
+    #[test]
+    fn color_chooser_keeps_its_value_on_cancel() {
+        let command = color_chooser_command(".r2", "::var1", Some(".r1"), false);
+        assert!(command.contains("if {$c ne \"\"} { set ::var1 $c ; catch { .r1 configure -background $c } }"));
+        assert!(!command.contains("puts"));
+        assert!(color_chooser_command(".r2", "::var1", None, true).ends_with("set ::var1 $c ; puts cb1s-.r2-$c ; flush stdout }"));
+    }
+
+    #[test]
+    fn tcl_string_quotes_special_characters() {
+        assert_eq!("\"plain text\"", tcl_string("plain text"));
+        assert_eq!("\"\\{a\\} \\[b\\] \\$c \\\"d\\\" e\\\\\"", tcl_string("{a} [b] $c \"d\" e\\"));
+        assert_eq!("\"one\\ntwo\"", tcl_string("one\ntwo"));
+    }
+
+    #[test]
+    fn keys_inside_frames() {
+        let inner = vec![ vec![input()], vec![frame("Nested", vec![vec![input(), button("Ok")]])] ];
+        let layout = vec![ vec![input(), frame("Options", inner)], vec![button("Ok")] ];
+        assert_eq!(vec!["input_0", "frame_0", "input_1", "frame_1", "input_2", "Ok", "Ok_1"], assign_keys(&layout));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+// Element keys, their Tk widget ids and the names their events report.
+
+use std::collections::HashMap;
+
+// Lookups both ways between keys and widget ids, so that Window methods
+// taking a key and `read()` receiving an id never scan the elements.
+#[derive(Default)]
+pub(crate) struct Registry {
+    // keys in layout order
+    keys: Vec<String>,
+    ids: HashMap<String, String>,
+    keys_by_id: HashMap<String, String>,
+    // elements reporting events by name rather than by key
+    names: HashMap<String, String>
+}
+
+impl Registry {
+    pub(crate) fn insert(&mut self, key: String, id: String) {
+        self.ids.insert(key.clone(), id.clone());
+        self.keys_by_id.insert(id, key.clone());
+        self.keys.push(key);
+    }
+
+    // Makes the element report events as `name`; the first name given wins,
+    // as with elements sharing a name.
+    pub(crate) fn set_event_name(&mut self, id: &str, name: &str) {
+        self.names.entry(id.to_string()).or_insert(name.to_string());
+    }
+
+    // Makes the element report events by its key even if it was given a name.
+    pub(crate) fn report_by_key(&mut self, id: &str) {
+        self.names.remove(id);
+    }
+
+    // Changes the name an element reporting by name reports, keeping its key.
+    pub(crate) fn rename(&mut self, id: &str, name: &str) {
+        if let Some(current) = self.names.get_mut(id) {
+            *current = name.to_string();
+        }
+    }
+
+    // Forgets the element, returning its widget id.
+    pub(crate) fn remove(&mut self, key: &str) -> Option<String> {
+        let id = self.ids.remove(key)?;
+        self.keys_by_id.remove(&id);
+        self.names.remove(&id);
+        self.keys.retain(|k| k != key);
+        return Some(id);
+    }
+
+    pub(crate) fn id(&self, key: &str) -> Option<&str> {
+        return self.ids.get(key).map(|id| id.as_str());
+    }
+
+    pub(crate) fn key(&self, id: &str) -> Option<&str> {
+        return self.keys_by_id.get(id).map(|key| key.as_str());
+    }
+
+    // What `read()` reports for an event from the element: its name, or else
+    // its key.
+    pub(crate) fn event_name(&self, id: &str) -> Option<&str> {
+        return self.names.get(id).or(self.keys_by_id.get(id)).map(|name| name.as_str());
+    }
+
+    pub(crate) fn keys(&self) -> &[String] {
+        return &self.keys;
+    }
+
+    // (key, id) pairs in layout order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        return self.keys.iter().map(|key| (key.as_str(), self.ids[key].as_str()));
+    }
+
+    // `base`, or the first "<base>_<n>" not taken yet, as assign_keys does.
+    pub(crate) fn unique_key(&self, base: &str) -> String {
+        if !self.ids.contains_key(base) {
+            return base.to_string();
+        }
+        let mut n = 1;
+        while self.ids.contains_key(&format!("{}_{}", base, n)) {
+            n += 1;
+        }
+        return format!("{}_{}", base, n);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::default();
+        registry.insert(String::from("Ok"), String::from(".r1"));
+        registry.set_event_name(".r1", "Ok");
+        registry.insert(String::from("input_0"), String::from(".r2"));
+        return registry;
+    }
+
+    #[test]
+    fn lookups_go_both_ways() {
+        let registry = registry();
+        assert_eq!(Some(".r2"), registry.id("input_0"));
+        assert_eq!(Some("input_0"), registry.key(".r2"));
+        assert_eq!(Some("Ok"), registry.event_name(".r1"));
+        assert_eq!(Some("input_0"), registry.event_name(".r2"));
+        assert_eq!(vec![("Ok", ".r1"), ("input_0", ".r2")], registry.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rename_keeps_the_key() {
+        let mut registry = registry();
+        registry.rename(".r1", "Apply");
+        assert_eq!(Some("Apply"), registry.event_name(".r1"));
+        assert_eq!(Some(".r1"), registry.id("Ok"));
+
+        // elements reporting by key keep doing so
+        registry.rename(".r2", "Name");
+        assert_eq!(Some("input_0"), registry.event_name(".r2"));
+    }
+
+    #[test]
+    fn explicit_keys_replace_the_name() {
+        let mut registry = registry();
+        registry.report_by_key(".r1");
+        assert_eq!(Some("Ok"), registry.event_name(".r1"));
+        registry.insert(String::from("save"), String::from(".r3"));
+        registry.set_event_name(".r3", "Save");
+        registry.report_by_key(".r3");
+        assert_eq!(Some("save"), registry.event_name(".r3"));
+    }
+
+    #[test]
+    fn removed_elements_are_forgotten() {
+        let mut registry = registry();
+        assert_eq!(Some(String::from(".r1")), registry.remove("Ok"));
+        assert_eq!(None, registry.id("Ok"));
+        assert_eq!(None, registry.event_name(".r1"));
+        assert_eq!(vec![String::from("input_0")], registry.keys());
+        assert_eq!(None, registry.remove("Ok"));
+    }
+
+    #[test]
+    fn taken_keys_get_a_suffix() {
+        let mut registry = registry();
+        assert_eq!("list_0", registry.unique_key("list_0"));
+        assert_eq!("Ok_1", registry.unique_key("Ok"));
+        registry.insert(String::from("Ok_1"), String::from(".r3"));
+        assert_eq!("Ok_2", registry.unique_key("Ok"));
+    }
+}
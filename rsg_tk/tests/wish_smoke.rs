@@ -0,0 +1,86 @@
+//! Smoke test against a real wish process.
+//!
+//! Ignored by default since it needs `wish` on the PATH and a display. On a
+//! headless machine run it under Xvfb:
+//!
+//! ```text
+//! xvfb-run cargo test --test wish_smoke -- --ignored
+//! ```
+//!
+//! rstk only supports one wish process per program, so everything runs in a
+//! single test.
+
+use std::io::Write;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use rsg_tk::*;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Starts a throwaway wish and checks it can reach a display.
+fn wish_available() -> bool {
+    let child = process::Command::new("wish")
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+    if let Some(stdin) = child.stdin.as_mut() {
+        let _ = stdin.write_all(b"puts ok ; flush stdout ; exit\n");
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || { let _ = sender.send(child.wait_with_output()); });
+    match receiver.recv_timeout(TIMEOUT) {
+        Ok(Ok(output)) => return output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "ok",
+        _ => return false,
+    }
+}
+
+// Presses and releases the left mouse button over the element, the same
+// way a user's click reaches Tk.
+fn click(window: &Window, key: &str) {
+    let id = window.widget_id(key).unwrap();
+    rstk::tell_wish(&format!(
+        "update ; event generate {id} <Enter> -x 2 -y 2 ; event generate {id} <ButtonPress-1> -x 2 -y 2 ; event generate {id} <ButtonRelease-1> -x 2 -y 2",
+        id = id
+    ));
+}
+
+// Runs `read()` on a helper thread so a window that never answers fails the
+// test instead of hanging it.
+fn read_with_timeout(window: Window) -> (String, Vec<String>) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || { let _ = sender.send(window.read()); });
+    return receiver.recv_timeout(TIMEOUT).expect("window did not report an event in time");
+}
+
+#[test]
+#[ignore]
+fn pyexample_button_click() {
+    if !wish_available() {
+        eprintln!("skipping: wish or a display is not available");
+        return;
+    }
+
+    let layout = vec![ vec![text("What's your name?")],
+                       vec![input()],
+                       vec![button("Ok")] ];
+    let window = window("Window Title", layout);
+
+    let input_id = window.widget_id("input_0").unwrap().to_string();
+    rstk::tell_wish(&format!("{} insert end {{Ferris {{the}} \"crab\"}}", input_id));
+    click(&window, "Ok");
+
+    let (event, values) = read_with_timeout(window);
+    assert_eq!("Ok", event);
+    assert_eq!(vec!["Ferris {the} \"crab\"".to_string()], values);
+
+    rstk::tell_wish("exit");
+}
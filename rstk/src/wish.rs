@@ -86,6 +86,7 @@
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Mutex;
 use std::thread;
@@ -110,19 +111,81 @@ fn tracing() -> bool {
 }
 
 static mut WISH: OnceCell<process::Child> = OnceCell::new();
-static mut OUTPUT: OnceCell<process::ChildStdout> = OnceCell::new();
 static mut SENDER: OnceCell<mpsc::Sender<String>> = OnceCell::new();
 
+// Set once wish is killed; messages to it are dropped from then on.
+static WISH_GONE: AtomicBool = AtomicBool::new(false);
+
 // Kills the wish process - should be called to exit
 pub(super) fn kill_wish() {
+    if WISH_GONE.swap(true, Ordering::SeqCst) {
+        return;
+    }
     unsafe {
-        WISH.get_mut()
-            .unwrap()
-            .kill()
-            .expect("Wish was unexpectedly already finished");
+        // wish may have exited on its own already
+        let _ = WISH.get_mut().unwrap().kill();
     }
 }
 
+/// Whether wish has been killed, e.g. after the window was closed.
+pub fn wish_gone() -> bool {
+    WISH_GONE.load(Ordering::SeqCst)
+}
+
+static RECORDING: Lazy<Mutex<Option<Vec<String>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Starts keeping a copy of every message sent to wish, in order, until
+/// [end_recording] is called.
+pub fn start_recording() {
+    *RECORDING.lock().unwrap() = Some(vec![]);
+}
+
+/// Stops recording and returns the messages sent since [start_recording].
+pub fn end_recording() -> Vec<String> {
+    RECORDING.lock().unwrap().take().unwrap_or_default()
+}
+
+static HELD: Lazy<Mutex<Option<Vec<String>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Holds back messages to wish until [release_held] is called, so that
+/// they are evaluated as a single script and Tk lays out and redraws the
+/// windows only once for all of them.
+pub fn hold() {
+    HELD.lock().unwrap().get_or_insert_with(Vec::new);
+}
+
+/// Stops holding messages and sends the held ones.
+pub fn release_held() {
+    let held = HELD.lock().unwrap().take();
+    if let Some(held) = held {
+        send_held(held);
+    }
+}
+
+/// Sends the messages held so far, and keeps holding the ones that follow.
+/// Needed before waiting for wish to act on one of them.
+pub fn flush_held() {
+    let held = HELD.lock().unwrap().as_mut().map(std::mem::take);
+    if let Some(held) = held {
+        send_held(held);
+    }
+}
+
+fn send_held(held: Vec<String>) {
+    if let Some(script) = held_script(&held) {
+        send(&script);
+    }
+}
+
+// Wraps held messages in one command, which wish only evaluates once it
+// has read all of it.
+fn held_script(held: &[String]) -> Option<String> {
+    if held.is_empty() {
+        return None;
+    }
+    Some(format!("eval {{\n{}\n}}", held.join("\n")))
+}
+
 /// Sends a message (tcl command) to wish.
 ///
 /// Use with caution: the message must be valid tcl.
@@ -131,9 +194,27 @@ pub fn tell_wish(msg: &str) {
     if tracing() {
         println!("wish: {}", msg);
     }
+    if let Some(recording) = RECORDING.lock().unwrap().as_mut() {
+        recording.push(String::from(msg));
+    }
+    if let Some(held) = HELD.lock().unwrap().as_mut() {
+        held.push(String::from(msg));
+        return;
+    }
+    send(msg);
+}
+
+fn send(msg: &str) {
+    if wish_gone() {
+        return;
+    }
     unsafe {
-        SENDER.get_mut().unwrap().send(String::from(msg)).unwrap();
-        SENDER.get_mut().unwrap().send(String::from("\n")).unwrap();
+        // one message per command, so commands told from different
+        // threads cannot interleave; before wish is started there is no
+        // one to tell, but the message is still recorded
+        if let Some(sender) = SENDER.get() {
+            sender.send(format!("{}\n", msg)).unwrap();
+        }
     }
 }
 
@@ -143,21 +224,141 @@ pub fn tell_wish(msg: &str) {
 /// Use with caution: the message must be valid tcl.
 ///
 pub fn ask_wish(msg: &str) -> String {
-    tell_wish(msg);
+    // the answer may depend on held messages, and must not itself be held
+    flush_held();
+    if tracing() {
+        println!("wish: {}", msg);
+    }
+    if let Some(recording) = RECORDING.lock().unwrap().as_mut() {
+        recording.push(String::from(msg));
+    }
+    if wish_gone() {
+        return String::new();
+    }
+    let query = format!("puts {} ; flush stdout\n{}\nputs {} ; flush stdout", ANSWER_BEGIN, msg, ANSWER_END);
+    let answer = transport().ask(|| send(&query));
+    match answer {
+        Some(answer) => {
+            if tracing() {
+                println!("---: {:?}", &answer);
+            }
+            answer
+        }
+        None => {
+            // wish has gone away, e.g. killed by the window manager
+            WISH_GONE.store(true, Ordering::SeqCst);
+            String::new()
+        }
+    }
+}
 
-    unsafe {
-        let mut input = [32; 10000]; // TODO - long inputs can get split?
-        if OUTPUT.get_mut().unwrap().read(&mut input).is_ok() {
-            if let Ok(input) = String::from_utf8(input.to_vec()) {
-                if tracing() {
-                    println!("---: {:?}", &input.trim());
-                }
-                return input.trim().to_string();
+// Printed around the output of a command sent with ask_wish, so that events
+// printed before or after it are not taken as part of the answer.
+const ANSWER_BEGIN: &str = "rsg-answer-begin";
+const ANSWER_END: &str = "rsg-answer-end";
+
+// Output read from wish and not handled yet. A read may return several
+// lines at once, or part of one.
+#[derive(Default)]
+struct Incoming {
+    partial: Vec<u8>,
+}
+
+// A line printed by wish, or the output of a command sent with ask_wish.
+#[derive(Debug, PartialEq)]
+enum Frame {
+    Event(String),
+    Answer(String),
+}
+
+impl Incoming {
+    // The next line from wish, or None once its output is closed.
+    fn next_line(&mut self, source: &mut impl Read) -> Option<String> {
+        loop {
+            if let Some(n) = self.partial.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.partial.drain(..=n).collect();
+                return Some(String::from_utf8_lossy(&line[..n]).to_string());
+            }
+            let mut input = [0; 10000];
+            match source.read(&mut input) {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => self.partial.extend_from_slice(&input[..n]),
             }
         }
     }
 
-    panic!("Eval-wish failed to get a result");
+    fn next_frame(&mut self, source: &mut impl Read) -> Option<Frame> {
+        let line = self.next_line(source)?;
+        if line != ANSWER_BEGIN {
+            return Some(Frame::Event(line));
+        }
+        let mut answer: Vec<String> = vec![];
+        loop {
+            let line = self.next_line(source)?;
+            if line == ANSWER_END {
+                break;
+            }
+            answer.push(line);
+        }
+        Some(Frame::Answer(answer.join("\n").trim().to_string()))
+    }
+}
+
+// Reads wish's output on its own thread and hands each frame to whoever
+// waits for it: answers to the caller of ask_wish, whichever thread that
+// is, and everything else to mainloop. Neither has to read, and so consume,
+// output meant for the other.
+struct Transport {
+    events: Mutex<mpsc::Receiver<String>>,
+    answers: Mutex<mpsc::Receiver<String>>,
+}
+
+static TRANSPORT: OnceCell<Transport> = OnceCell::new();
+
+impl Transport {
+    fn start(mut source: impl Read + Send + 'static) -> Transport {
+        let (event_sender, events) = mpsc::channel();
+        let (answer_sender, answers) = mpsc::channel();
+        thread::spawn(move || {
+            let mut incoming = Incoming::default();
+            while let Some(frame) = incoming.next_frame(&mut source) {
+                // a receiver only goes away with the whole program
+                let _ = match frame {
+                    Frame::Event(line) => event_sender.send(line),
+                    Frame::Answer(answer) => answer_sender.send(answer),
+                };
+            }
+        });
+        Transport {
+            events: Mutex::new(events),
+            answers: Mutex::new(answers),
+        }
+    }
+
+    // Sends a query with `send` and waits for its answer, or None once
+    // wish's output is closed. Queries from different threads take turns,
+    // so that each gets the answer to its own.
+    fn ask(&self, send: impl FnOnce()) -> Option<String> {
+        let answers = self.answers.lock().unwrap();
+        send();
+        answers.recv().ok()
+    }
+
+    // The next line which is not part of an answer, or None once wish's
+    // output is closed.
+    fn next_line(&self) -> Option<String> {
+        self.events.lock().unwrap().recv().ok()
+    }
+
+    // The next line which is not part of an answer if it has been read
+    // already, without waiting for one.
+    fn try_line(&self) -> Result<String, mpsc::TryRecvError> {
+        self.events.lock().unwrap().try_recv()
+    }
+}
+
+fn transport() -> &'static Transport {
+    TRANSPORT.get().expect("wish is not started")
 }
 
 // -- Counter for making new ids
@@ -389,36 +590,97 @@ pub fn testerr(thing: TkText) {
 }
 
 /// Loops while GUI events occur
+///
+/// Returns the next event, or None once wish has gone. Events read before
+/// wish went away are still returned first. Output from wish that is not an
+/// event is skipped.
 pub fn mainloop() -> Option<String> {
-    unsafe {
-            let mut input = [32; 10000];
-            if OUTPUT.get_mut().unwrap().read(&mut input).is_ok() {
-                if let Ok(input) = String::from_utf8(input.to_vec()) {
-    
-                    if input.starts_with("clicked") {
-                        if let Some(n) = input.find('\n') {
-                            let widget = &input[8..n];
-                            return Some(widget.to_string());
-                        }
+    quit_on_exit(next_event(transport(), &WISH_GONE, true))
+}
+
+/// Returns the next event wish has reported so far, or None right away if
+/// there is none. Unlike [mainloop] it does not wait for wish.
+pub fn pending_event() -> Option<String> {
+    quit_on_exit(next_event(transport(), &WISH_GONE, false))
+}
+
+// Kills wish once it reported that the window was closed.
+fn quit_on_exit(event: Option<String>) -> Option<String> {
+    if event.as_deref() == Some("Quit") {
+        kill_wish();
+    }
+    event
+}
+
+// The next event from `transport`, skipping output which is not one. Only
+// waits for wish if `wait`, and while `gone` is not set; the events already
+// read are drained either way, so none is lost when wish goes away.
+fn next_event(transport: &Transport, gone: &AtomicBool, wait: bool) -> Option<String> {
+    loop {
+        let line = match transport.try_line() {
+            Ok(line) => line,
+            Err(mpsc::TryRecvError::Empty) if wait && !gone.load(Ordering::SeqCst) => {
+                match transport.next_line() {
+                    Some(line) => line,
+                    None => {
+                        gone.store(true, Ordering::SeqCst);
                         return None;
-                    } else if input.starts_with("cb1b") {
-                        let parts: Vec<&str> = input.split("-").collect();
-                        let widget = parts[1].trim();
-                        let value = parts[2].trim();
-                        return Some(widget.to_owned() + &format!("-cbsep-{}", value == "1"));
-                    } else if input.starts_with("cb1") {
-                        let parts: Vec<&str> = input.split("-").collect();
-                        let widget = parts[1].trim();
-                        return Some(widget.to_owned());
-                    } else if input.starts_with("exit") {
-                        kill_wish();
-                        return Some("Quit".to_string())
-                    }
-                    return None; // exit loop and program
                     }
                 }
-                return None
             }
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                gone.store(true, Ordering::SeqCst);
+                return None;
+            }
+        };
+        match parse_event(&line) {
+            Some(event) => return Some(event),
+            None => {
+                if tracing() {
+                    println!("wish: skipping unexpected output {:?}", line);
+                }
+            }
+        }
+    }
+}
+
+// Turns a line printed by wish into the event mainloop reports.
+fn parse_event(input: &str) -> Option<String> {
+    if input.starts_with("clicked") {
+        return input.get(8..).map(|widget| widget.to_string());
+    } else if input.starts_with("cb1b") {
+        let parts: Vec<&str> = input.split("-").collect();
+        if parts.len() < 3 { return None; }
+        let widget = parts[1].trim();
+        let value = parts[2].trim();
+        // "mixed" is the third state of tristate checkbuttons
+        let state = match value { "1" => "true", "mixed" => "mixed", _ => "false" };
+        return Some(widget.to_owned() + &format!("-cbsep-{}", state));
+    } else if input.starts_with("cb1s") || input.starts_with("cb1f") {
+        // string values, and negative numbers, may themselves contain '-'
+        let parts: Vec<&str> = input.splitn(3, "-").collect();
+        if parts.len() < 3 { return None; }
+        let widget = parts[1].trim();
+        let value = parts[2].trim();
+        return Some(widget.to_owned() + &format!("-cbsep-{}", value));
+    } else if input.starts_with("cb1") {
+        let parts: Vec<&str> = input.split("-").collect();
+        if parts.len() < 2 { return None; }
+        let widget = parts[1].trim();
+        return Some(widget.to_owned());
+    } else if input.starts_with("timeout-") {
+        return Some(input.trim().to_string());
+    } else if input.starts_with("injected") {
+        return Some("injected".to_string());
+    } else if input.starts_with("close-request") {
+        return Some("close-request".to_string());
+    } else if input.starts_with("restored") {
+        return Some("restored".to_string());
+    } else if input.starts_with("exit") {
+        return Some("Quit".to_string());
+    }
+    None // not an event
 }
 
 /// Creates a connection with the "wish" program.
@@ -466,12 +728,10 @@ fn start_tk_connection(wish: &str)-> Result<toplevel::TkTopLevel, TkError> {
                 };
 
         let mut input = WISH.get_mut().unwrap().stdin.take().unwrap();
-        if OUTPUT
-            .set(WISH.get_mut().unwrap().stdout.take().unwrap())
-                .is_err()
-                {
-                    return Err(TkError { message: err_msg });
-                }
+        let output = WISH.get_mut().unwrap().stdout.take().unwrap();
+        if TRANSPORT.set(Transport::start(output)).is_err() {
+            return Err(TkError { message: err_msg });
+        }
 
         // -- initial setup of Tcl/Tk environment
 
@@ -564,6 +824,192 @@ pub(super) fn split_items(text: &str) -> Vec<String> {
     mod tests {
         use super::*;
 
+        // Hands out the scripted output a few bytes at a time, the way a
+        // pipe may split it.
+        struct Script {
+            output: Vec<u8>,
+            chunk: usize,
+        }
+
+        impl Read for Script {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.chunk.min(self.output.len()).min(buf.len());
+                buf[..n].copy_from_slice(&self.output[..n]);
+                self.output.drain(..n);
+                Ok(n)
+            }
+        }
+
+        fn events(incoming: &mut Incoming, script: &mut Script) -> Vec<String> {
+            let mut events = vec![];
+            while let Some(frame) = incoming.next_frame(script) {
+                if let Frame::Event(line) = frame {
+                    events.push(parse_event(&line).unwrap_or_default());
+                }
+            }
+            events
+        }
+
+        // Output written by the test while the reader waits for it, as it
+        // waits for wish.
+        struct Pipe {
+            output: mpsc::Receiver<Vec<u8>>,
+        }
+
+        impl Read for Pipe {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.output.recv() {
+                    Ok(output) => {
+                        buf[..output.len()].copy_from_slice(&output);
+                        Ok(output.len())
+                    }
+                    Err(_) => Ok(0),
+                }
+            }
+        }
+
+        #[test]
+        fn click_then_close_delivers_both_in_order() {
+            let mut script = Script { output: b"clicked-.r3\nexit\n".to_vec(), chunk: 10000 };
+            assert_eq!(vec![".r3", "Quit"], events(&mut Incoming::default(), &mut script));
+        }
+
+        #[test]
+        fn close_then_click_reports_the_close_first() {
+            let (wish, output) = mpsc::channel();
+            let transport = Transport::start(Pipe { output });
+            let gone = AtomicBool::new(false);
+            wish.send(b"exit\nclicked-.r3\n".to_vec()).unwrap();
+            assert_eq!(Some(String::from("Quit")), next_event(&transport, &gone, true));
+            // the click has been read once an answer printed after it has
+            let answer = transport.ask(|| {
+                wish.send(format!("{}\n{}\n", ANSWER_BEGIN, ANSWER_END).into_bytes()).unwrap();
+            });
+            assert_eq!(Some(String::new()), answer);
+            // as kill_wish does; the click is still delivered, and then
+            // there is no waiting on the open pipe
+            gone.store(true, Ordering::SeqCst);
+            assert_eq!(Some(String::from(".r3")), next_event(&transport, &gone, true));
+            assert_eq!(None, next_event(&transport, &gone, true));
+        }
+
+        #[test]
+        fn events_read_before_wish_went_away_are_kept() {
+            let (wish, output) = mpsc::channel();
+            let transport = Transport::start(Pipe { output });
+            let gone = AtomicBool::new(false);
+            wish.send(b"clicked-.r3\nnoise\nclicked-.r4\n".to_vec()).unwrap();
+            drop(wish);
+            assert_eq!(Some(String::from(".r3")), next_event(&transport, &gone, true));
+            assert_eq!(Some(String::from(".r4")), next_event(&transport, &gone, true));
+            assert_eq!(None, next_event(&transport, &gone, true));
+            assert!(gone.load(Ordering::SeqCst));
+        }
+
+        #[test]
+        fn events_around_an_answer_are_kept() {
+            let output = format!("clicked-.r3\n{}\nFerris\n\n{}\nexit\n", ANSWER_BEGIN, ANSWER_END);
+            let mut script = Script { output: output.into_bytes(), chunk: 5 };
+            let mut incoming = Incoming::default();
+            assert_eq!(Some(Frame::Event(String::from("clicked-.r3"))), incoming.next_frame(&mut script));
+            assert_eq!(Some(Frame::Answer(String::from("Ferris"))), incoming.next_frame(&mut script));
+            assert_eq!(Some(Frame::Event(String::from("exit"))), incoming.next_frame(&mut script));
+            assert_eq!(None, incoming.next_frame(&mut script));
+        }
+
+        #[test]
+        fn a_query_gets_its_answer_while_the_mainloop_waits() {
+            let (wish, output) = mpsc::channel();
+            let transport = Transport::start(Pipe { output });
+            thread::scope(|scope| {
+                let mainloop = scope.spawn(|| transport.next_line());
+                // the mainloop is blocked on the events until the click
+                let answer = transport.ask(|| {
+                    wish.send(format!("{}\nFerris\n{}\n", ANSWER_BEGIN, ANSWER_END).into_bytes()).unwrap();
+                });
+                assert_eq!(Some(String::from("Ferris")), answer);
+                wish.send(b"clicked-.r3\n".to_vec()).unwrap();
+                assert_eq!(Some(String::from("clicked-.r3")), mainloop.join().unwrap());
+            });
+            drop(wish);
+            assert_eq!(None, transport.ask(|| {}));
+            assert_eq!(None, transport.next_line());
+            assert_eq!(Err(mpsc::TryRecvError::Disconnected), transport.try_line());
+        }
+
+        #[test]
+        fn polling_does_not_wait_for_wish() {
+            let (wish, output) = mpsc::channel();
+            let transport = Transport::start(Pipe { output });
+            assert_eq!(Err(mpsc::TryRecvError::Empty), transport.try_line());
+            // the event is read before the answer printed after it
+            let answer = transport.ask(|| {
+                wish.send(format!("clicked-.r3\n{}\n{}\n", ANSWER_BEGIN, ANSWER_END).into_bytes()).unwrap();
+            });
+            assert_eq!(Some(String::new()), answer);
+            assert_eq!(Ok(String::from("clicked-.r3")), transport.try_line());
+            assert_eq!(Err(mpsc::TryRecvError::Empty), transport.try_line());
+        }
+
+        #[test]
+        fn timeouts_keep_their_number() {
+            let mut script = Script { output: b"timeout-3\nclicked-.r1\n".to_vec(), chunk: 4 };
+            assert_eq!(vec!["timeout-3", ".r1"], events(&mut Incoming::default(), &mut script));
+        }
+
+        #[test]
+        fn checkbutton_states_are_named() {
+            let mut script = Script { output: b"cb1b-.r2-1\ncb1b-.r2-0\ncb1b-.r2-mixed\n".to_vec(), chunk: 10000 };
+            assert_eq!(vec![".r2-cbsep-true", ".r2-cbsep-false", ".r2-cbsep-mixed"], events(&mut Incoming::default(), &mut script));
+        }
+
+        #[test]
+        fn scale_values_are_kept() {
+            let mut script = Script { output: b"cb1f-.r2-42.5\ncb1f-.r2--3\n".to_vec(), chunk: 10000 };
+            assert_eq!(vec![".r2-cbsep-42.5", ".r2-cbsep--3"], events(&mut Incoming::default(), &mut script));
+        }
+
+        // Lines wish should never print, and junk it might print anyway.
+        fn nasty_output() -> Vec<u8> {
+            let mut output: Vec<u8> = vec![];
+            for line in ["clicked", "clicked-", "cb1", "cb1-", "cb1b", "cb1b-", "cb1b-.r1", "cb1s", "cb1s-",
+                         "cb1s-.r1", "cb1s--", "clicked-\0\0", "\0cb1s-\0-\0", "clicked-.r1 9 999999999999",
+                         "exitcb1s", "rsg-answer-end", "\u{feff}clicked-.r1", "clicked-.r1 \u{1F600}"] {
+                output.extend_from_slice(line.as_bytes());
+                output.push(b'\n');
+            }
+            output.extend(std::iter::repeat(b'c').take(16 * 1024));
+            output.push(b'\n');
+            // binary junk from a fixed LCG, so failures can be replayed
+            let mut state: u32 = 0x2545_f491;
+            for _ in 0..64 * 1024 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                output.push((state >> 16) as u8);
+            }
+            output.extend_from_slice(&[0xff, 0xfe, b'\n', 0xc3, b'\n', 0xe2, 0x82]);
+            output
+        }
+
+        #[test]
+        fn no_output_makes_the_event_parser_panic() {
+            for chunk in [1, 7, 4096] {
+                let mut script = Script { output: nasty_output(), chunk };
+                let mut incoming = Incoming::default();
+                while let Some(frame) = incoming.next_frame(&mut script) {
+                    if let Frame::Event(line) = frame {
+                        let _ = parse_event(&line);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn held_messages_become_one_script() {
+            assert_eq!(None, held_script(&[]));
+            let held = vec![String::from("grid .r1"), String::from("grid remove .r2")];
+            assert_eq!(Some(String::from("eval {\ngrid .r1\ngrid remove .r2\n}")), held_script(&held));
+        }
+
         #[test]
         fn split_items_1() {
             let result = split_items("");
@@ -24,44 +24,143 @@ use rsg_tk::radio as _radio;
 use rsg_tk::radio_ex as _radio_ex;
 use rsg_tk::input as _input;
 use rsg_tk::input_ex as _input_ex;
+use rsg_tk::input_validated as _input_validated;
+use rsg_tk::input_validated_ex as _input_validated_ex;
+use rsg_tk::number_input as _number_input;
+use rsg_tk::number_input_ex as _number_input_ex;
 use rsg_tk::slider as _slider;
 use rsg_tk::slider_ex as _slider_ex;
+use rsg_tk::vslider as _vslider;
+use rsg_tk::vslider_ex as _vslider_ex;
 use rsg_tk::separator as _separator;
+use rsg_tk::hseparator as _hseparator;
+use rsg_tk::hseparator_ex as _hseparator_ex;
+use rsg_tk::vseparator as _vseparator;
+use rsg_tk::vseparator_ex as _vseparator_ex;
 use rsg_tk::separator_ex as _separator_ex;
+use rsg_tk::spin as _spin;
+use rsg_tk::spin_ex as _spin_ex;
+use rsg_tk::progress_bar as _progress_bar;
+use rsg_tk::progress_bar_ex as _progress_bar_ex;
+use rsg_tk::image as _image;
+use rsg_tk::image_ex as _image_ex;
+use rsg_tk::image_from_bytes as _image_from_bytes;
+use rsg_tk::image_from_bytes_ex as _image_from_bytes_ex;
+use rsg_tk::frame as _frame;
+use rsg_tk::frame_ex as _frame_ex;
+use rsg_tk::column as _column;
+use rsg_tk::column_ex as _column_ex;
+use rsg_tk::paned as _paned;
+use rsg_tk::paned_ex as _paned_ex;
+use rsg_tk::kv as _kv;
+use rsg_tk::kv_ex as _kv_ex;
+use rsg_tk::canvas as _canvas;
+use rsg_tk::canvas_ex as _canvas_ex;
+use rsg_tk::gauge as _gauge;
+use rsg_tk::gauge_ex as _gauge_ex;
+use rsg_tk::graph as _graph;
+use rsg_tk::graph_ex as _graph_ex;
+use rsg_tk::output as _output;
+use rsg_tk::output_ex as _output_ex;
+use rsg_tk::scrolled_text as _scrolled_text;
+use rsg_tk::scrolled_text_ex as _scrolled_text_ex;
+use rsg_tk::titlebar as _titlebar;
+use rsg_tk::titlebar_ex as _titlebar_ex;
+use rsg_tk::menu_item as _menu_item;
+use rsg_tk::menu_check as _menu_check;
+use rsg_tk::menu_radio as _menu_radio;
+use rsg_tk::menu_separator as _menu_separator;
+use rsg_tk::submenu as _submenu;
+use rsg_tk::status_bar as _status_bar;
+use rsg_tk::status_bar_ex as _status_bar_ex;
+use rsg_tk::segmented as _segmented;
+use rsg_tk::segmented_ex as _segmented_ex;
+use rsg_tk::radio_group as _radio_group;
+use rsg_tk::radio_group_ex as _radio_group_ex;
+use rsg_tk::checklist as _checklist;
+use rsg_tk::checklist_ex as _checklist_ex;
+use rsg_tk::folder_browse as _folder_browse;
+use rsg_tk::folder_browse_ex as _folder_browse_ex;
+use rsg_tk::color_chooser as _color_chooser;
+use rsg_tk::color_chooser_ex as _color_chooser_ex;
+use rsg_tk::option_menu as _option_menu;
+use rsg_tk::sizegrip as _sizegrip;
+use rsg_tk::option_menu_ex as _option_menu_ex;
+use rsg_tk::button_menu as _button_menu;
+use rsg_tk::button_menu_ex as _button_menu_ex;
+use rsg_tk::validate_layout as _validate_layout;
+use rsg_tk::is_cursor as _is_cursor;
+use rsg_tk::window_strict as _window_strict;
+use rsg_tk::window_ex as _window_ex;
+use rsg_tk::window_at as _window_at;
+use rsg_tk::window_strict_ex as _window_strict_ex;
 pub use rsg_tk::RsgColor as RsgColor;
 pub use rsg_tk::RsgObjEx as RsgObjEx;
+pub use rsg_tk::RsgWindowEx as RsgWindowEx;
+pub use rsg_tk::RsgFont as RsgFont;
 pub use rsg_tk::RsgOrientation as RsgOrientation;
+pub use rsg_tk::RsgJustify as RsgJustify;
+pub use rsg_tk::RsgRelief as RsgRelief;
+pub use rsg_tk::RsgError as RsgError;
+pub use rsg_tk::RsgEvent as RsgEvent;
+pub use rsg_tk::RsgModifiers as RsgModifiers;
+pub use rsg_tk::RsgMenuItem as RsgMenuItem;
+pub use rsg_tk::RsgMenuKind as RsgMenuKind;
+pub use rsg_tk::WindowHandle as WindowHandle;
+pub use rsg_tk::Frozen as Frozen;
+pub use rsg_tk::WIN_CLOSED as WIN_CLOSED;
+pub use rsg_tk::TIMEOUT_KEY as TIMEOUT_KEY;
+pub use rsg_tk::RESTORED_KEY as RESTORED_KEY;
+pub use rsg_tk::DROP_SOURCE_KEY as DROP_SOURCE_KEY;
+pub use rsg_tk::DROP_TEXT_KEY as DROP_TEXT_KEY;
+pub use rsg_tk::VALID_KEY_SUFFIX as VALID_KEY_SUFFIX;
+pub use rsg_tk::RsgCanvas as RsgCanvas;
+pub use rsg_tk::RsgElement as RsgElement;
+pub use rsg_tk::rsg_print as rsg_print;
+pub use rsg_tk::rsg_println as rsg_println;
+pub use rsg_tk::RsgValue as RsgValue;
+pub use rsg_tk::Values as Values;
+pub use rsg_tk::set_decimal_separator as set_decimal_separator;
+pub use rsg_tk::decimal_separator as decimal_separator;
+pub use rsg_tk::parse_decimal as parse_decimal;
+pub use rsg_tk::format_decimal as format_decimal;
+pub use rsg_tk::set_options as set_options;
+pub use rsg_tk::options as options;
+pub use rsg_tk::RsgOptions as RsgOptions;
+pub use rsg_tk::LayoutIssue as LayoutIssue;
+pub use rsg_tk::LayoutIssueKind as LayoutIssueKind;
+pub use rsg_tk::set_warning_hook as set_warning_hook;
 
 
 
-pub fn text<T>(text_name: T) -> RsgObj where String: From<T> {
+pub fn text(text_name: impl Into<String>) -> RsgObj {
     return _text(text_name);
 }
-pub fn text_ex<T, U>(text_name: T, text_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn text_ex<U>(text_name: impl Into<String>, text_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return _text_ex(text_name, text_ex);
 }
 
 
-pub fn button<T>(button_name: T) -> RsgObj where String: From<T> {
+pub fn button(button_name: impl Into<String>) -> RsgObj {
     return _button(button_name);
 }
-pub fn button_ex<T, U>(button_name: T, button_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn button_ex<U>(button_name: impl Into<String>, button_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return _button_ex(button_name, button_ex);
 }
 
 
-pub fn checkbox<T>(checkbox_name: T) -> RsgObj where String: From<T> {
+pub fn checkbox(checkbox_name: impl Into<String>) -> RsgObj {
     return _checkbox(checkbox_name);
 }
-pub fn checkbox_ex<T, U>(checkbox_name: T, checkbox_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn checkbox_ex<U>(checkbox_name: impl Into<String>, checkbox_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return _checkbox_ex(checkbox_name, checkbox_ex);
 }
 
 
-pub fn radio<T>(radio_name: T) -> RsgObj where String: From<T> {
+pub fn radio(radio_name: impl Into<String>) -> RsgObj {
     return _radio(radio_name);
 }
-pub fn radio_ex<T, U>(radio_name: T, radio_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn radio_ex<U>(radio_name: impl Into<String>, radio_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return _radio_ex(radio_name, radio_ex);
 }
 
@@ -69,19 +168,49 @@ pub fn radio_ex<T, U>(radio_name: T, radio_ex: U) -> RsgObj where String: From<T
 pub fn input() -> RsgObj {
     return _input();
 }
-pub fn input_ex<T, U>(input_placeholder: T, input_ex: U) -> RsgObj where String: From<T>, RsgObjEx: From<U>, U: Copy {
+pub fn input_ex<U>(input_placeholder: impl Into<String>, input_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
     return _input_ex(input_placeholder, input_ex);
 }
+pub fn input_validated(check: impl Fn(&str) -> bool + Send + Sync + 'static) -> RsgObj {
+    return _input_validated(check);
+}
+pub fn input_validated_ex<U>(input_placeholder: impl Into<String>, check: impl Fn(&str) -> bool + Send + Sync + 'static, input_validated_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _input_validated_ex(input_placeholder, check, input_validated_ex);
+}
+pub fn number_input() -> RsgObj {
+    return _number_input();
+}
+pub fn number_input_ex<U>(number_input_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _number_input_ex(number_input_ex);
+}
 
 
 pub fn slider() -> RsgObj {
     return _slider();
 }
-pub fn slider_ex<T, U>(slider_orientation: T, slider_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy, {
+pub fn slider_ex<T, U>(slider_orientation: T, slider_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
     return _slider_ex(slider_orientation, slider_ex);
 }
+pub fn vslider() -> RsgObj {
+    return _vslider();
+}
+pub fn vslider_ex<U>(vslider_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _vslider_ex(vslider_ex);
+}
 
 
+pub fn hseparator() -> RsgObj {
+    return _hseparator();
+}
+pub fn hseparator_ex<U>(hseparator_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _hseparator_ex(hseparator_ex);
+}
+pub fn vseparator() -> RsgObj {
+    return _vseparator();
+}
+pub fn vseparator_ex<U>(vseparator_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _vseparator_ex(vseparator_ex);
+}
 pub fn separator() -> RsgObj {
     return _separator();
 }
@@ -90,6 +219,218 @@ pub fn separator_ex<T, U>(separator_orientaiton: T, separator_ex: U) -> RsgObj w
 }
 
 
-pub fn window<T, U>(window_name: T, layout: U) -> Window where String: From<T>, Vec<Vec<RsgObj>>: From<U> {
+pub fn spin() -> RsgObj {
+    return _spin();
+}
+pub fn spin_ex<U>(spin_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _spin_ex(spin_ex);
+}
+
+
+pub fn progress_bar() -> RsgObj {
+    return _progress_bar();
+}
+pub fn progress_bar_ex<T, U>(progress_bar_orientation: T, progress_bar_ex: U) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<U>, U: Copy {
+    return _progress_bar_ex(progress_bar_orientation, progress_bar_ex);
+}
+
+
+pub fn image(image_path: impl Into<String>) -> RsgObj {
+    return _image(image_path);
+}
+pub fn image_ex<U>(image_path: impl Into<String>, image_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _image_ex(image_path, image_ex);
+}
+pub fn image_from_bytes(data: &[u8]) -> RsgObj {
+    return _image_from_bytes(data);
+}
+pub fn image_from_bytes_ex<U>(data: &[u8], image_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _image_from_bytes_ex(data, image_ex);
+}
+
+
+pub fn frame(frame_title: impl Into<String>, frame_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> RsgObj {
+    return _frame(frame_title, frame_layout);
+}
+pub fn frame_ex<V>(frame_title: impl Into<String>, frame_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, frame_ex: V) -> RsgObj where RsgObjEx: From<V>, V: Copy {
+    return _frame_ex(frame_title, frame_layout, frame_ex);
+}
+
+
+pub fn column(column_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> RsgObj {
+    return _column(column_layout);
+}
+pub fn column_ex<V>(column_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, column_ex: V) -> RsgObj where RsgObjEx: From<V>, V: Copy {
+    return _column_ex(column_layout, column_ex);
+}
+pub fn paned<T>(first_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, second_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, paned_orientation: T) -> RsgObj where RsgOrientation: From<T> {
+    return _paned(first_layout, second_layout, paned_orientation);
+}
+pub fn paned_ex<T, V>(first_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, second_layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, paned_orientation: T, paned_ex: V) -> RsgObj where RsgOrientation: From<T>, RsgObjEx: From<V>, V: Copy {
+    return _paned_ex(first_layout, second_layout, paned_orientation, paned_ex);
+}
+
+
+pub fn kv(kv_label: impl Into<String>, kv_value: impl Into<String>) -> RsgObj {
+    return _kv(kv_label, kv_value);
+}
+pub fn kv_ex<U>(kv_label: impl Into<String>, kv_value: impl Into<String>, kv_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _kv_ex(kv_label, kv_value, kv_ex);
+}
+
+
+pub fn canvas() -> RsgObj {
+    return _canvas();
+}
+pub fn canvas_ex<U>(canvas_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _canvas_ex(canvas_ex);
+}
+
+
+pub fn gauge(min: i64, max: u64) -> RsgObj {
+    return _gauge(min, max);
+}
+pub fn gauge_ex<U>(min: i64, max: u64, gauge_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _gauge_ex(min, max, gauge_ex);
+}
+
+
+pub fn graph() -> RsgObj {
+    return _graph();
+}
+pub fn graph_ex<U>(graph_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _graph_ex(graph_ex);
+}
+
+
+pub fn output() -> RsgObj {
+    return _output();
+}
+pub fn output_ex<U>(output_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _output_ex(output_ex);
+}
+
+
+pub fn scrolled_text() -> RsgObj {
+    return _scrolled_text();
+}
+pub fn scrolled_text_ex<U>(scrolled_text_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _scrolled_text_ex(scrolled_text_ex);
+}
+
+
+pub fn titlebar(title: impl Into<String>) -> RsgObj {
+    return _titlebar(title);
+}
+pub fn titlebar_ex<U>(title: impl Into<String>, titlebar_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _titlebar_ex(title, titlebar_ex);
+}
+
+
+pub fn menu_item(label: impl Into<String>) -> RsgMenuItem {
+    return _menu_item(label);
+}
+pub fn menu_check(label: impl Into<String>, checked: bool) -> RsgMenuItem {
+    return _menu_check(label, checked);
+}
+pub fn menu_radio(label: impl Into<String>, group: impl Into<String>) -> RsgMenuItem {
+    return _menu_radio(label, group);
+}
+pub fn menu_separator() -> RsgMenuItem {
+    return _menu_separator();
+}
+pub fn submenu(label: impl Into<String>, items: impl IntoIterator<Item = RsgMenuItem>) -> RsgMenuItem {
+    return _submenu(label, items);
+}
+
+
+pub fn status_bar(status_text: impl Into<String>) -> RsgObj {
+    return _status_bar(status_text);
+}
+pub fn status_bar_ex<U>(status_text: impl Into<String>, status_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _status_bar_ex(status_text, status_ex);
+}
+
+
+pub fn segmented(options: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return _segmented(options);
+}
+pub fn segmented_ex<U>(options: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>, segmented_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _segmented_ex(options, default, segmented_ex);
+}
+
+
+pub fn radio_group(options: impl IntoIterator<Item = impl Into<String>>, default_index: usize) -> RsgObj {
+    return _radio_group(options, default_index);
+}
+pub fn radio_group_ex<U>(options: impl IntoIterator<Item = impl Into<String>>, default_index: usize, radio_group_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _radio_group_ex(options, default_index, radio_group_ex);
+}
+
+
+pub fn checklist(items: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return _checklist(items);
+}
+pub fn checklist_ex<U>(items: impl IntoIterator<Item = impl Into<String>>, checklist_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _checklist_ex(items, checklist_ex);
+}
+
+
+pub fn folder_browse(initial_dir: impl Into<String>) -> RsgObj {
+    return _folder_browse(initial_dir);
+}
+pub fn folder_browse_ex<U>(initial_dir: impl Into<String>, folder_browse_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _folder_browse_ex(initial_dir, folder_browse_ex);
+}
+
+
+pub fn color_chooser() -> RsgObj {
+    return _color_chooser();
+}
+pub fn color_chooser_ex<U>(color_chooser_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _color_chooser_ex(color_chooser_ex);
+}
+
+
+pub fn option_menu(choices: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>) -> RsgObj {
+    return _option_menu(choices, default);
+}
+pub fn option_menu_ex<U>(choices: impl IntoIterator<Item = impl Into<String>>, default: impl Into<String>, option_menu_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _option_menu_ex(choices, default, option_menu_ex);
+}
+
+
+pub fn button_menu(button_text: impl Into<String>, entries: impl IntoIterator<Item = impl Into<String>>) -> RsgObj {
+    return _button_menu(button_text, entries);
+}
+pub fn button_menu_ex<U>(button_text: impl Into<String>, entries: impl IntoIterator<Item = impl Into<String>>, button_menu_ex: U) -> RsgObj where RsgObjEx: From<U>, U: Copy {
+    return _button_menu_ex(button_text, entries, button_menu_ex);
+}
+
+
+pub fn sizegrip() -> RsgObj {
+    return _sizegrip();
+}
+
+
+pub fn window(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> Window {
     return _window(window_name, layout);
-}
\ No newline at end of file
+}
+pub fn window_ex<V>(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, window_ex: V) -> Window where RsgWindowEx: From<V> {
+    return _window_ex(window_name, layout, window_ex);
+}
+pub fn window_at(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, location: (i64, i64)) -> Window {
+    return _window_at(window_name, layout, location);
+}
+pub fn window_strict(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>) -> Result<Window, RsgError> {
+    return _window_strict(window_name, layout);
+}
+pub fn window_strict_ex<V>(window_name: impl Into<String>, layout: impl IntoIterator<Item = impl IntoIterator<Item = RsgObj>>, window_ex: V) -> Result<Window, RsgError> where RsgWindowEx: From<V> {
+    return _window_strict_ex(window_name, layout, window_ex);
+}
+pub fn validate_layout(layout: &Vec<Vec<RsgObj>>) -> Vec<LayoutIssue> {
+    return _validate_layout(layout);
+}
+pub fn is_cursor(name: &str) -> bool {
+    return _is_cursor(name);
+}